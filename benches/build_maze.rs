@@ -0,0 +1,21 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use rayon::ThreadPoolBuilder;
+
+use brickadia_gen_v4::{MazeTopology, ThetaMaze};
+
+/// Builds the same maze on one thread and on every thread, to compare the rings built in parallel with a serial build.
+fn build_maze(c: &mut Criterion) {
+    let maze = ThetaMaze::builder().rings(12).ring_width(120).initial_divisions(4).generate().unwrap();
+
+    let mut group = c.benchmark_group("build_maze");
+    group.sample_size(10);
+
+    let serial = ThreadPoolBuilder::new().num_threads(1).build().unwrap();
+    group.bench_function("one thread", |b| b.iter(|| serial.install(|| maze.build(false, false))));
+    group.bench_function("all threads", |b| b.iter(|| maze.build(false, false)));
+
+    group.finish();
+}
+
+criterion_group!(benches, build_maze);
+criterion_main!(benches);
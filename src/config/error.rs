@@ -0,0 +1,47 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// Reasons a maze configuration could not be loaded, or a builder could not generate its maze.
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, std::io::Error),
+    /// The file extension is neither `.toml` nor `.json`.
+    Format(PathBuf),
+    Toml(toml::de::Error),
+    Json(serde_json::Error),
+    UnknownPreset(String),
+    /// A value was parsed but is outside of its allowed range.
+    Invalid { field: &'static str, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "failed to read {}: {}", path.display(), e),
+            Self::Format(path) => write!(f, "{} is not a .toml or .json file", path.display()),
+            Self::Toml(e) => write!(f, "invalid TOML config: {}", e),
+            Self::Json(e) => write!(f, "invalid JSON config: {}", e),
+            Self::UnknownPreset(name) => write!(
+                f,
+                "unknown preset '{}', expected one of: {}",
+                name,
+                super::presets::PRESETS.map(|(name, _)| name).join(", ")
+            ),
+            Self::Invalid { field, reason } => write!(f, "invalid `{}`: {}", field, reason),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        Self::Toml(e)
+    }
+}
+
+impl From<serde_json::Error> for ConfigError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
@@ -0,0 +1,416 @@
+use std::f32::consts::PI;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use image::Rgb;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+use crate::geometry::arc::{Precision, WedgeStyle, MIN_TOLERANCE};
+use crate::geometry::decompose::Partition;
+use crate::maze::{
+    Algorithm, DeltaLayout, GridLayout, HexLayout, Layout, MazeTopology, RowsMaze, RowsMazeBuilder, Selection, Slab,
+    Subdivision, ThetaMaze, ThetaMazeBuilder,
+};
+use super::error::ConfigError;
+use super::presets::preset;
+
+/// The shape of the maze cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum Topology {
+    /// Concentric rings divided into cells.
+    #[default]
+    Theta,
+    /// Rows of square cells.
+    Grid,
+    /// Rows of hexagonal cells.
+    Hex,
+    /// Rows of triangular cells, alternately pointing up and down.
+    Delta,
+}
+
+/// Describes a maze and where it's written, loaded from a `.toml` or `.json` file.
+/// <br> Missing keys take their default values, unknown keys are rejected.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MazeConfig {
+    pub topology: Topology,
+    pub algorithm: Algorithm,
+    /// The growing tree policy, such as `75% newest / 25% random`.
+    pub selection: Selection,
+    /// The fraction of dead ends opened into loops, from `0.0` for a perfect maze to `1.0`.
+    pub braid: f32,
+    pub rings: usize,
+    /// Width of each ring in micro-bricks.
+    pub ring_width: u32,
+    pub initial_divisions: usize,
+    /// `doubling` every two rings, or `{ arc_length = 1.5 }` ring widths before a ring's cells are split.
+    pub subdivision: Subdivision,
+    /// Cells across a grid, hex or delta maze.
+    pub columns: usize,
+    /// Rows of cells down a grid, hex or delta maze.
+    pub rows: usize,
+    /// Micro-bricks between the walls of a grid cell, between the centres of hex cells, or along the sides of delta cells.
+    pub cell_size: u32,
+    /// Thickness of the walls in micro-bricks, for every topology.
+    pub radius_gap: u32,
+    pub wall_height: u32,
+    /// Height of the bottom of the walls above the ground.
+    pub elevation: i32,
+    /// `[r, g, b]` of every wall. The rings are shaded when omitted.
+    pub wall_colour: Option<[u8; 3]>,
+    /// `simple` or `corners` curved walls.
+    pub wedge_style: WedgeStyle,
+    /// `{ tolerance = 0.5 }` or `{ max_wedge_length = 30 }` micro-bricks.
+    pub precision: Precision,
+    /// `greedy`, or `minimum` for fewer bricks at the cost of build time.
+    pub partition: Partition,
+    /// A layer of bricks under the walls, `[floor]` with `colour`, `material` and `thickness` keys.
+    /// <br> A disc under theta mazes, the outline of the outer walls under the others.
+    pub floor: Option<Slab>,
+    /// A layer of bricks over the walls, with the same keys as the floor.
+    pub ceiling: Option<Slab>,
+    pub seed: (u32, u32, u32, u32),
+    pub output: OutputConfig,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Name of the `.brs` save, without the extension.
+    pub name: String,
+    /// Directory of the save. Defaults to the Brickadia builds folder.
+    pub directory: Option<PathBuf>,
+    pub draw: bool,
+    pub solve: bool,
+    pub image: PathBuf,
+    pub bricks_image: PathBuf,
+}
+
+impl Default for MazeConfig {
+    fn default() -> Self {
+        Self {
+            topology: Topology::Theta,
+            algorithm: Algorithm::default(),
+            selection: Selection::default(),
+            braid: 0.0,
+            rings: 3,
+            ring_width: 100,
+            initial_divisions: 2,
+            subdivision: Subdivision::default(),
+            columns: 10,
+            rows: 10,
+            cell_size: 100,
+            radius_gap: 50,
+            wall_height: 100,
+            elevation: 0,
+            wall_colour: None,
+            wedge_style: WedgeStyle::default(),
+            precision: Precision::default(),
+            partition: Partition::default(),
+            floor: None,
+            ceiling: None,
+            seed: (11, 12, 15, 2),
+            output: OutputConfig::default(),
+        }
+    }
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            name: "Maze".into(),
+            directory: None,
+            draw: true,
+            solve: false,
+            image: "maze.png".into(),
+            bricks_image: "maze_bricks.png".into(),
+        }
+    }
+}
+
+/// Taller walls are stacked from several bricks, this only catches mistyped heights.
+const MAX_WALL_HEIGHT: u32 = 100_000;
+const MAX_RINGS: usize = 128;
+/// The fewest cells the innermost ring around the centre may have.
+const MIN_DIVISIONS: usize = 2;
+const MAX_RING_WIDTH: u32 = 10_000;
+const MAX_CELLS_ACROSS: usize = 1_000;
+
+impl MazeConfig {
+    /// Loads a config, choosing the format from the file extension.
+    pub fn from_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml(&text),
+            Some("json") => Self::from_json(&text),
+            _ => Err(ConfigError::Format(path.to_path_buf())),
+        }
+    }
+
+    pub fn from_toml(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = toml::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, ConfigError> {
+        let config: Self = serde_json::from_str(text)?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Loads one of the built-in presets: `small`, `medium` or `huge`.
+    pub fn preset(name: &str) -> Result<Self, ConfigError> {
+        let toml = preset(name).ok_or_else(|| ConfigError::UnknownPreset(name.into()))?;
+        Self::from_toml(toml)
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(self).expect("MazeConfig is always serializable")
+    }
+
+    /// Checks the values that would otherwise panic or produce a broken maze.
+    /// <br> Only the keys of the chosen topology are checked, the others are ignored.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let invalid = |field, reason: String| Err(ConfigError::Invalid { field, reason });
+
+        if !(0.0..=1.0).contains(&self.braid) {
+            return invalid("braid", format!("{} is not between 0.0 and 1.0", self.braid));
+        }
+        if !(1..=MAX_WALL_HEIGHT).contains(&self.wall_height) {
+            return invalid("wall_height", format!("{} is not between 1 and {}", self.wall_height, MAX_WALL_HEIGHT));
+        }
+        for (field, slab) in [("floor", &self.floor), ("ceiling", &self.ceiling)] {
+            if slab.is_some_and(|slab| slab.thickness == 0) {
+                return invalid(field, "the thickness must be at least 1".into());
+            }
+        }
+
+        match self.topology {
+            Topology::Theta => self.validate_theta(),
+            topology => self.validate_rows(topology),
+        }
+    }
+
+    fn validate_theta(&self) -> Result<(), ConfigError> {
+        let invalid = |field, reason: String| Err(ConfigError::Invalid { field, reason });
+
+        if !(2..=MAX_RINGS).contains(&self.rings) {
+            return invalid("rings", format!("{} is not between 2 and {}", self.rings, MAX_RINGS));
+        }
+        if self.initial_divisions == 0 {
+            return invalid("initial_divisions", "must be at least 1".into());
+        }
+        if !(1..=MAX_RING_WIDTH).contains(&self.ring_width) {
+            return invalid("ring_width", format!("{} is not between 1 and {}", self.ring_width, MAX_RING_WIDTH));
+        }
+        if self.radius_gap == 0 || self.radius_gap > self.ring_width {
+            return invalid(
+                "radius_gap",
+                format!("{} is not between 1 and the ring width {}", self.radius_gap, self.ring_width),
+            );
+        }
+        // The passage round a ring is what's left of its width after the wall.
+        if self.ring_width < 2 * self.radius_gap {
+            return invalid(
+                "ring_width",
+                format!(
+                    "{} leaves the passages narrower than the walls ({}), it must be at least twice the radius gap",
+                    self.ring_width, self.radius_gap
+                ),
+            );
+        }
+        match self.subdivision {
+            Subdivision::ArcLength(length) if !length.is_finite() || length <= 0.0 => {
+                return invalid("subdivision", format!("the arc length {} is not above 0", length));
+            }
+            _ => {}
+        }
+        match self.precision {
+            Precision::Tolerance(tolerance) if tolerance.is_nan() || tolerance < MIN_TOLERANCE => {
+                return invalid("precision", format!("the tolerance {} is below {}", tolerance, MIN_TOLERANCE));
+            }
+            Precision::MaxWedgeLength(0) => return invalid("precision", "the wedge length must be at least 1".into()),
+            _ => {}
+        }
+        // The centre opens into the innermost ring, which needs cells enough to branch around it.
+        let divisions = self.subdivision.divisions(self.initial_divisions, self.rings);
+        if divisions[1] < MIN_DIVISIONS {
+            return invalid(
+                "initial_divisions",
+                format!("the innermost ring has a single cell, it needs at least {}", MIN_DIVISIONS),
+            );
+        }
+
+        // Doubling, or splitting against a short arc length, may leave the cells thinner than their walls.
+        for (ring, &ring_divisions) in divisions.iter().enumerate().skip(1) {
+            let circumference = 2.0 * PI * (ring as u32 * self.ring_width) as f32;
+            let cell_width = circumference / ring_divisions as f32;
+            if cell_width < self.radius_gap as f32 {
+                return invalid(
+                    "rings",
+                    format!(
+                        "the cells of ring {} are {:.0} micro-bricks wide, thinner than the walls ({})",
+                        ring, cell_width, self.radius_gap
+                    ),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the keys of grid, hex and delta mazes.
+    fn validate_rows(&self, topology: Topology) -> Result<(), ConfigError> {
+        let invalid = |field, reason: String| Err(ConfigError::Invalid { field, reason });
+
+        for (field, count) in [("columns", self.columns), ("rows", self.rows)] {
+            if !(1..=MAX_CELLS_ACROSS).contains(&count) {
+                return invalid(field, format!("{} is not between 1 and {}", count, MAX_CELLS_ACROSS));
+            }
+        }
+        if !(1..=MAX_RING_WIDTH).contains(&self.cell_size) {
+            return invalid("cell_size", format!("{} is not between 1 and {}", self.cell_size, MAX_RING_WIDTH));
+        }
+
+        // The walls of a delta cell take their thickness from each of its three sides.
+        let room = match topology {
+            Topology::Delta => self.cell_size as f32 / 3f32.sqrt(),
+            _ => self.cell_size as f32,
+        };
+        if self.radius_gap == 0 || self.radius_gap as f32 >= room {
+            return invalid(
+                "radius_gap",
+                format!("{} is not between 1 and {:.0}, the room inside the cells", self.radius_gap, room),
+            );
+        }
+        Ok(())
+    }
+
+    /// A builder for the theta maze of the config. Only the theta keys are read, whatever its topology.
+    pub fn theta_builder(&self) -> ThetaMazeBuilder {
+        ThetaMaze::builder()
+            .algorithm(self.algorithm)
+            .selection(self.selection)
+            .braid(self.braid)
+            .rings(self.rings)
+            .ring_width(self.ring_width)
+            .initial_divisions(self.initial_divisions)
+            .subdivision(self.subdivision)
+            .radius_gap(self.radius_gap)
+            .wall_height(self.wall_height)
+            .elevation(self.elevation)
+            .wall_colour(self.wall_colour.map(Rgb))
+            .wedge_style(self.wedge_style)
+            .precision(self.precision)
+            .partition(self.partition)
+            .floor(self.floor)
+            .ceiling(self.ceiling)
+            .seed(self.seed)
+    }
+
+    /// A builder for the grid, hex or delta maze of the config laid out by `L`, whatever its topology.
+    /// <br> The radius gap sets the thickness of the walls.
+    pub fn rows_builder<L: Layout>(&self) -> RowsMazeBuilder<L> {
+        RowsMaze::<L>::builder()
+            .columns(self.columns)
+            .rows(self.rows)
+            .cell_size(self.cell_size)
+            .algorithm(self.algorithm)
+            .selection(self.selection)
+            .braid(self.braid)
+            .wall_thickness(self.radius_gap)
+            .wall_height(self.wall_height)
+            .elevation(self.elevation)
+            .wall_colour(self.wall_colour.map(Rgb))
+            .floor(self.floor)
+            .ceiling(self.ceiling)
+            .seed(self.seed)
+    }
+
+    /// Validates the config and generates the maze of the chosen topology.
+    pub fn generate(&self) -> Result<Box<dyn MazeTopology>, ConfigError> {
+        self.validate()?;
+        Ok(match self.topology {
+            Topology::Theta => Box::new(self.theta_builder().generate()?),
+            Topology::Grid => Box::new(self.rows_builder::<GridLayout>().generate()?),
+            Topology::Hex => Box::new(self.rows_builder::<HexLayout>().generate()?),
+            Topology::Delta => Box::new(self.rows_builder::<DeltaLayout>().generate()?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod load_config {
+
+    use super::*;
+    use super::super::presets::PRESETS;
+    use crate::metadata::assets::BrickMaterials;
+
+    #[test]
+    fn run() {
+
+        for (name, _) in PRESETS {
+            MazeConfig::preset(name).unwrap();
+        }
+
+        let json = r#"{ "rings": 5, "algorithm": "wilson", "seed": [1, 2, 3, 4], "output": { "solve": true } }"#;
+        let config = MazeConfig::from_json(json).unwrap();
+        assert_eq!(config.rings, 5);
+        assert_eq!(config.algorithm, Algorithm::Wilson);
+        assert_eq!(config.seed, (1, 2, 3, 4));
+        assert!(config.output.solve);
+        assert_eq!(MazeConfig::from_toml(&config.to_toml()).unwrap(), config);
+
+        assert!(matches!(MazeConfig::from_toml("ring = 5"), Err(ConfigError::Toml(_))));
+        assert!(matches!(MazeConfig::from_toml("selection = \"90% newest\""), Err(ConfigError::Toml(_))));
+        assert!(matches!(MazeConfig::from_toml("rings = 1"), Err(ConfigError::Invalid { field: "rings", .. })));
+        assert!(matches!(MazeConfig::from_toml("radius_gap = 500"), Err(ConfigError::Invalid { field: "radius_gap", .. })));
+        assert!(matches!(
+            MazeConfig::from_toml("ring_width = 1\nradius_gap = 1"),
+            Err(ConfigError::Invalid { field: "ring_width", .. })
+        ));
+        assert!(matches!(
+            MazeConfig::from_toml("rings = 2\ninitial_divisions = 1"),
+            Err(ConfigError::Invalid { field: "initial_divisions", .. })
+        ));
+        assert!(matches!(MazeConfig::from_toml("braid = 1.5"), Err(ConfigError::Invalid { field: "braid", .. })));
+        assert!(matches!(
+            MazeConfig::from_toml("precision = { tolerance = 0.0 }"),
+            Err(ConfigError::Invalid { field: "precision", .. })
+        ));
+        assert!(matches!(
+            MazeConfig::from_toml("precision = { tolerance = 1e-9 }"),
+            Err(ConfigError::Invalid { field: "precision", .. })
+        ));
+        assert_eq!(MazeConfig::from_toml("precision = { tolerance = 0.5 }").unwrap().precision, Precision::Tolerance(0.5));
+        let config = MazeConfig::from_toml("[floor]\nmaterial = \"metallic\"").unwrap();
+        assert_eq!(config.floor.unwrap().material, BrickMaterials::Metallic);
+        assert!(matches!(MazeConfig::from_toml("[ceiling]\nthickness = 0"), Err(ConfigError::Invalid { field: "ceiling", .. })));
+        let config = MazeConfig::from_toml("topology = \"hex\"\n[floor]\nthickness = 5").unwrap();
+        assert!(config.generate().is_ok());
+        assert!(matches!(
+            MazeConfig::from_toml("topology = \"grid\"\n[ceiling]\nthickness = 0"),
+            Err(ConfigError::Invalid { field: "ceiling", .. })
+        ));
+        assert!(matches!(MazeConfig::preset("tiny"), Err(ConfigError::UnknownPreset(_))));
+
+        // Doubling leaves the cells of a hundred rings far thinner than their walls, splitting by arc length doesn't.
+        assert!(matches!(MazeConfig::from_toml("rings = 100"), Err(ConfigError::Invalid { field: "rings", .. })));
+        let config = MazeConfig::from_toml("rings = 100
+subdivision = { arc_length = 1.5 }").unwrap();
+        assert_eq!(config.subdivision, Subdivision::ArcLength(1.5));
+        assert!(matches!(
+            MazeConfig::from_toml("subdivision = { arc_length = -1.0 }"),
+            Err(ConfigError::Invalid { field: "subdivision", .. })
+        ));
+
+        // Splitting against a tiny arc length leaves cells thinner than their walls, even in a few rings.
+        assert!(matches!(
+            MazeConfig::from_toml("subdivision = { arc_length = 0.01 }"),
+            Err(ConfigError::Invalid { field: "rings", .. })
+        ));
+    }
+}
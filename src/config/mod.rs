@@ -0,0 +1,6 @@
+pub mod error;
+pub mod maze_config;
+pub mod presets;
+
+pub use maze_config::{MazeConfig, OutputConfig, Topology};
+pub use error::ConfigError;
@@ -0,0 +1,14 @@
+/// Built-in configurations, by name.
+/// <br> The files double as examples of the config format.
+pub const PRESETS: [(&str, &str); 3] = [
+    ("small", include_str!("../../presets/small.toml")),
+    ("medium", include_str!("../../presets/medium.toml")),
+    ("huge", include_str!("../../presets/huge.toml")),
+];
+
+pub fn preset(name: &str) -> Option<&'static str> {
+    PRESETS
+        .iter()
+        .find(|(preset, _)| preset.eq_ignore_ascii_case(name))
+        .map(|(_, toml)| *toml)
+}
@@ -1,32 +1,81 @@
-use image::{Rgb, RgbImage};
+use image::{ImageResult, Rgb, RgbImage};
 use imageproc::drawing::draw_line_segment_mut;
 
 use crate::utils::points::Point;
 
 
+/// A call to one of the drawing functions, kept to be drawn on an image later.
+#[derive(Clone, Debug)]
+pub enum Drawing {
+    Arc { centre: (f32, f32), radius: f32, start_angle: f32, end_angle: f32, color: Rgb<u8> },
+    Point(Point, Rgb<u8>),
+    Line((f32, f32), (f32, f32), Rgb<u8>),
+    LineSeries(Vec<Point>, Rgb<u8>),
+    Outline(Vec<Point>, Rgb<u8>),
+    FilledPolygon(Vec<Point>, Rgb<u8>),
+}
+
+impl Drawing {
+    pub fn draw(&self, img: &mut RgbImage) {
+        match self {
+            Self::Arc { centre: (x, y), radius, start_angle, end_angle, color } => {
+                draw_arc(img, *x, *y, *radius, *start_angle, *end_angle, *color)
+            }
+            Self::Point(p, color) => draw_point(img, *p, *color),
+            Self::Line(begin, end, color) => draw_line_segment_mut(img, *begin, *end, *color),
+            Self::LineSeries(points, color) => draw_line_series(img, points, *color),
+            Self::Outline(polygon, color) => draw_outline(img, polygon, *color),
+            Self::FilledPolygon(polygon, color) => draw_filled_polygon(img, polygon, *color),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct DebugImage {
     img: Option<RgbImage>,
+    /// The drawings of a recorder, in order, until they're replayed on an image.
+    drawings: Option<Vec<Drawing>>,
     pub rgb: Rgb<u8>
 }
 
 impl DebugImage {
     pub fn new(img: Option<RgbImage>, rgb: Rgb<u8>) -> Self {
-        Self { img, rgb }
+        Self { img, drawings: None, rgb }
     }
 
-    /// If an image exists, perform an action on it.
-    fn with_image<F>(&mut self, action: F)
+    /// An empty debug image that keeps the drawings for `replay`, if this one is drawn at all.
+    /// <br> Lets parallel tasks draw without sharing the image.
+    pub fn recorder(&self) -> Self {
+        Self { img: None, drawings: self.is_active().then(Vec::new), rgb: self.rgb }
+    }
+
+    /// Draws everything the recorder kept, in the order it was drawn.
+    pub fn replay(&mut self, recorder: DebugImage) {
+        for drawing in recorder.drawings.into_iter().flatten() {
+            if let Some(ref mut img) = self.img {
+                drawing.draw(img);
+            } else if let Some(ref mut drawings) = self.drawings {
+                drawings.push(drawing);
+            }
+        }
+    }
+
+    /// Draws straight on the image, or keeps the drawing if recording.
+    /// <br> The drawing is only made when recording, so live images don't copy the points.
+    fn draw<F, D>(&mut self, live: F, drawing: D)
     where
         F: FnOnce(&mut RgbImage),
+        D: FnOnce() -> Drawing,
     {
         if let Some(ref mut img) = self.img {
-            action(img);
+            live(img);
+        } else if let Some(ref mut drawings) = self.drawings {
+            drawings.push(drawing());
         }
     }
 
     pub fn is_active(&self) -> bool {
-        self.img.is_some()
+        self.img.is_some() || self.drawings.is_some()
     }
 
     pub fn draw_arc(
@@ -38,11 +87,14 @@ impl DebugImage {
         end_angle: f32,
         color: Rgb<u8>,
     ) {
-        self.with_image(|img| draw_arc(img, center_x, center_y, radius, start_angle, end_angle, color));
+        self.draw(
+            |img| draw_arc(img, center_x, center_y, radius, start_angle, end_angle, color),
+            || Drawing::Arc { centre: (center_x, center_y), radius, start_angle, end_angle, color },
+        );
     }
 
     pub fn draw_point(&mut self, p: Point, color: Rgb<u8>) {
-        self.with_image(|img| draw_point(img, p, color));
+        self.draw(|img| draw_point(img, p, color), || Drawing::Point(p, color));
     }
 
     pub fn draw_line(
@@ -51,7 +103,7 @@ impl DebugImage {
         end: (f32, f32),
         color: Rgb<u8>,
     ) {
-        self.with_image(|img| draw_line_segment_mut(img, begin, end, color));
+        self.draw(|img| draw_line_segment_mut(img, begin, end, color), || Drawing::Line(begin, end, color));
     
     }
 
@@ -59,24 +111,25 @@ impl DebugImage {
         if points.len() < 2 {
             return; // or handle the error as needed
         }
-        self.with_image(|img| {
-            for pair in points.windows(2) {
-                draw_line_segment_mut(img, pair[0].tuple(), pair[1].tuple(), color);
-            }
-        });
+        self.draw(|img| draw_line_series(img, points, color), || Drawing::LineSeries(points.to_vec(), color));
     }
 
     pub fn draw_outline(&mut self, polygon: &[Point], color: Rgb<u8>) {
-        self.with_image(|img| draw_outline(img, polygon, color));
+        self.draw(|img| draw_outline(img, polygon, color), || Drawing::Outline(polygon.to_vec(), color));
     }
 
     pub fn draw_filled_polygon(&mut self, polygon: &[Point], color: Rgb<u8>) {
-        self.with_image(|img| draw_filled_polygon(img, polygon, color));
+        self.draw(|img| draw_filled_polygon(img, polygon, color), || Drawing::FilledPolygon(polygon.to_vec(), color));
     }
 
-    pub fn save(&self, path: &str) {
-        if let Some(ref img) = self.img {
-            img.save(path).expect("Failed to save image");
+    pub fn into_image(self) -> Option<RgbImage> {
+        self.img
+    }
+
+    pub fn save(&self, path: &str) -> ImageResult<()> {
+        match self.img {
+            Some(ref img) => img.save(path),
+            None => Ok(()),
         }
     }
 }
@@ -93,6 +146,13 @@ fn draw_point(img: &mut RgbImage, p: Point, color: Rgb<u8>) {
     img.put_pixel(x as u32, y as u32, color);
 }
 
+/// Draws the lines between consecutive points.
+fn draw_line_series(img: &mut RgbImage, points: &[Point], color: Rgb<u8>) {
+    for pair in points.windows(2) {
+        draw_line_segment_mut(img, pair[0].tuple(), pair[1].tuple(), color);
+    }
+}
+
 /// Draws an outline given by a loop of points.
 pub fn draw_outline(img: &mut RgbImage, polygon: &[Point], color: Rgb<u8>) {
     if polygon.len() < 2 {
@@ -1,26 +1,97 @@
 
-use std::{cmp::max, f32::consts::PI};
+use std::f32::consts::PI;
+use std::fmt;
+use std::str::FromStr;
 use brickadia::save::{Brick, Direction, Rotation, Size};
 use crate::draw::colors::rgb_to_brick;
 use crate::draw::draw::DebugImage;
 use crate::metadata::assets::BrickAssets;
 use crate::utils::points::Point;
 use image::Rgb;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
 use super::polygon::Polygon;
 
-use super::decompose::render_as_squares;
+use super::decompose::{render_as_squares_with, stack_layers, Partition};
 
 pub const SEMICIRCLE: f32 = PI;
 pub const CIRCLE: f32 = PI * 2.0;
 
+/// How closely the wedge steps of an `Arc` follow its circle.
+/// <br> Parsed from `tolerance=0.5` or `max_wedge_length=30`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Precision {
+    /// The largest gap, in micro-bricks, between a step and the circle.
+    Tolerance(f32),
+    /// The length of a step in micro-bricks. The arc is split into as many whole lengths as fit along it,
+    /// <br> rounded down as the walls have always been stepped, so a step runs up to twice as long.
+    /// <br> Higher values lead to less precise approximations.
+    MaxWedgeLength(u32),
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Self::MaxWedgeLength(30)
+    }
+}
+
+/// The smallest tolerance, in micro-bricks, a `Precision` accepts.
+pub const MIN_TOLERANCE: f32 = 0.01;
+
+impl Precision {
+    /// The number of steps an arc of `radius` spanning `angle_span` is split into, at least one.
+    /// <br> A tolerance step never covers less than a micro-brick of arc, so tiny tolerances can't ask for endless steps.
+    pub fn steps(&self, radius: f32, angle_span: f32) -> usize {
+        let steps = match *self {
+            Self::Tolerance(tolerance) => {
+                let step_angle = 2.0 * (1.0 - tolerance / radius).max(-1.0).acos();
+                (angle_span / step_angle.max(1.0 / radius)).ceil() as usize
+            }
+            Self::MaxWedgeLength(length) => (radius * angle_span).ceil() as usize / length as usize,
+        };
+        steps.max(1)
+    }
+}
+
+impl fmt::Display for Precision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tolerance(tolerance) => write!(f, "tolerance={}", tolerance),
+            Self::MaxWedgeLength(length) => write!(f, "max_wedge_length={}", length),
+        }
+    }
+}
+
+impl FromStr for Precision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = s.split_once('=').ok_or_else(|| format!("expected name=value, found '{}'", s))?;
+        let value = value.trim();
+        let precision = match name.trim() {
+            "tolerance" => Self::Tolerance(value.parse().map_err(|e| format!("'{}': {}", value, e))?),
+            "max_wedge_length" => Self::MaxWedgeLength(value.parse().map_err(|e| format!("'{}': {}", value, e))?),
+            other => return Err(format!("unknown precision '{}', expected tolerance or max_wedge_length", other)),
+        };
+        match precision {
+            Self::Tolerance(tolerance) if tolerance.is_nan() || tolerance < MIN_TOLERANCE => {
+                Err(format!("the tolerance must be at least {}", MIN_TOLERANCE))
+            }
+            Self::MaxWedgeLength(0) => Err("the wedge length must be at least 1".into()),
+            precision => Ok(precision),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Arc {
-    pub(crate)begin_angle: f32,
-    pub(crate)end_angle: f32,
-    /// Higher values lead to less precise approximations.
-    pub(crate)centre: Point,
-    pub(crate)radius: u32,
-    pub(crate)is_inner_arc: bool,
+    pub begin_angle: f32,
+    pub end_angle: f32,
+    pub centre: Point,
+    pub radius: u32,
+    pub is_inner_arc: bool,
+    pub precision: Precision,
 }
 impl Arc {
 
@@ -35,28 +106,50 @@ impl Arc {
         (p.x >= self.centre.x) == (p.y >= self.centre.y)
     }
     
+    /// The lower angle of the arc and the angle it spans, whichever way round its ends were given.
+    fn span(&self) -> (f32, f32) {
+        (self.begin_angle.min(self.end_angle), (self.end_angle - self.begin_angle).abs())
+    }
+
     fn vertex_angles(&self, angle_step: f32, steps: usize) -> impl DoubleEndedIterator<Item = (f32, f32, f32)> {
+        let begin_angle = self.span().0;
         (0..steps).map(move |i| {
-            let angle1 = self.begin_angle + i as f32 * angle_step;
+            let angle1 = begin_angle + i as f32 * angle_step;
             let angle2 = angle1 + angle_step;
             let mid = (angle1 + angle2) / 2.0;
             (angle1, mid, angle2)
         })
     }
 
+    /// The number of wedge steps needed to meet the precision.
+    pub fn steps(&self) -> usize {
+        self.precision.steps(self.radius as f32, self.span().1)
+    }
+
+    /// The largest gap, in micro-bricks, between the steps and the circle.
+    pub fn deviation(&self) -> f32 {
+        self.split_deviation(1)
+    }
+
+    /// The largest gap, in micro-bricks, between the circle and the steps split into `parts` each.
+    pub fn split_deviation(&self, parts: usize) -> f32 {
+        let angle_step = self.span().1 / (self.steps() * parts) as f32;
+        self.radius as f32 * (1.0 - (angle_step / 2.0).cos())
+    }
+
     pub fn vertex_points(&self) -> impl DoubleEndedIterator<Item = [Point; 3]> {
+        self.split_vertex_points(1)
+    }
 
-        let p = |angle| self.coordinate(angle);
+    /// The right-angled triangles of the steps split into `parts` each, every corner of the split on the circle.
+    pub fn split_vertex_points(&self, parts: usize) -> impl DoubleEndedIterator<Item = [Point; 3]> {
 
-        let angle_span = self.end_angle - self.begin_angle;
-        let raw_steps = (self.radius as f32 * angle_span).ceil() as usize;
-        
-        let steps = max(raw_steps / 30, 1);
+        let p = |angle| self.coordinate(angle);
 
+        let angle_span = self.span().1;
+        let steps = self.steps() * parts;
         let angle_step = angle_span / steps as f32;
 
-        //println!("Steps: {}, {}", raw_steps, steps);
-
         self.vertex_angles(angle_step, steps)
             .map(move |(angle1, mid, angle2)| {
 
@@ -83,6 +176,110 @@ impl Arc {
 }
 
 
+/// How the steps of a `WedgeArc` are joined.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum WedgeStyle {
+    /// `MicroWedge` steps meeting at the corners of the `MicroBrick` fill.
+    #[default]
+    Simple,
+    /// Steps through the point of the circle halfway along each simple step as well, and caps the tip of the fill
+    /// <br> where adjacent steps meet with a corner wedge. From above, the walls keep twice as close to their circles.
+    Corners,
+}
+
+impl WedgeStyle {
+    /// The wedges each step of the arc's `Precision` is split into.
+    pub fn parts(self) -> usize {
+        match self {
+            Self::Simple => 1,
+            Self::Corners => 2,
+        }
+    }
+}
+
+/// The `z` position of a brick `height` tall whose bottom is `elevation` above the ground.
+/// <br> Like `x` and `y`, positions are twice the size units.
+pub fn brick_z(height: u32, elevation: i32) -> i32 {
+    elevation * 2 + height as i32
+}
+
+/// `MicroWedge`s filling the right-angled triangle `[p1, p90, p2]`, or none if it is flat.
+/// <br> The bricks are stacked `height` tall from `elevation` above the ground.
+pub fn wedge_bricks(wedge: &Polygon, rgb: Rgb<u8>, height: u32, elevation: i32) -> Vec<Brick> {
+    triangle_bricks(BrickAssets::MicroWedge, wedge, rgb, height, elevation)
+}
+
+/// Bricks of any wedge `asset` filling the right-angled triangle `[p1, p90, p2]`, or none if it is flat.
+pub fn triangle_bricks(asset: BrickAssets, wedge: &Polygon, rgb: Rgb<u8>, height: u32, elevation: i32) -> Vec<Brick> {
+    let (size, position) = (wedge.size, wedge.position);
+    let p90 = wedge.points[1];
+    if size.x == 0 || size.y == 0 {
+        return Vec::new();
+    }
+
+    stack_layers(height, elevation)
+        .into_iter()
+        .map(|(height, elevation)| Brick {
+            asset_name_index: asset.index() as u32,
+            color: rgb_to_brick(rgb),
+            size: Size::Procedural(size.x, size.y, height),
+            position: (position.x, position.y, brick_z(height, elevation)),
+            rotation: if p90.y == wedge.max.y { Rotation::Deg180 } else { Rotation::Deg0 },
+            direction: if p90 == wedge.min || p90 == wedge.max { Direction::ZPositive } else { Direction::ZNegative },
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// A corner wedge `asset` on the right-angled triangle `[p1, tip, p2]`, or none if it is flat.
+/// <br> The corner assets rest with their tip towards `+x` and `+y`, half a turn from the right angle of a resting
+/// <br> `MicroWedge`, and are only turned about `z` so their slopes keep facing up.
+pub fn corner_bricks(asset: BrickAssets, corner: &Polygon, rgb: Rgb<u8>, height: u32, elevation: i32) -> Vec<Brick> {
+    let (size, position) = (corner.size, corner.position);
+    let tip = corner.points[1];
+    if size.x == 0 || size.y == 0 {
+        return Vec::new();
+    }
+
+    let rotation = match (tip.x == corner.max.x, tip.y == corner.max.y) {
+        (true, true) => Rotation::Deg0,
+        (false, true) => Rotation::Deg90,
+        (false, false) => Rotation::Deg180,
+        (true, false) => Rotation::Deg270,
+    };
+
+    stack_layers(height, elevation)
+        .into_iter()
+        .map(|(height, elevation)| Brick {
+            asset_name_index: asset.index() as u32,
+            color: rgb_to_brick(rgb),
+            size: Size::Procedural(size.x, size.y, height),
+            position: (position.x, position.y, brick_z(height, elevation)),
+            rotation,
+            direction: Direction::ZPositive,
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// `MicroBrick`s filling an axis aligned rectangle, stacked `height` tall from `elevation` above the ground.
+pub fn rectangle_bricks(rectangle: &Polygon, rgb: Rgb<u8>, height: u32, elevation: i32) -> Vec<Brick> {
+    let (size, position) = (rectangle.size, rectangle.position);
+    stack_layers(height, elevation)
+        .into_iter()
+        .map(|(height, elevation)| Brick {
+            asset_name_index: BrickAssets::MicroBrick.index() as u32,
+            color: rgb_to_brick(rgb),
+            size: Size::Procedural(size.x, size.y, height),
+            material_index: 0,
+            position: (position.x, position.y, brick_z(height, elevation)),
+            ..Default::default()
+        })
+        .collect()
+}
+
 pub struct Wedge {
     brick: Brick,
     points: [Point; 3]
@@ -91,7 +288,15 @@ pub struct Wedge {
 pub struct WedgeArc {
     pub rgb: Rgb<u8>,
     pub arc: Arc,
+    /// Thickness of the arc, measured inwards from its radius.
     pub radius_gap: u32,
+    /// Vertical size of the bricks.
+    pub height: u32,
+    /// Height of the bottom of the bricks above the ground.
+    pub elevation: i32,
+    pub style: WedgeStyle,
+    /// How the rectangles between the wedges are merged.
+    pub partition: Partition,
 }
 
 impl WedgeArc {
@@ -130,34 +335,40 @@ impl WedgeArc {
         [corner_begin, corner_end]
     }
 
-    fn wedge_brick(&self, wedge: &Polygon) -> Option<Brick> {
-        let (size, position) = (wedge.size, wedge.position);
-        let p90 = wedge.points[1];
-        if size.x != 0 && size.y != 0 {
-            Some(Brick {
-                asset_name_index: BrickAssets::MicroWedge.index() as u32,
-                color: rgb_to_brick(self.rgb),
-                size: Size::Procedural(size.x, size.y, 100),
-                position: (position.x, position.y, 100),
-                rotation: if p90.y == wedge.max.y { Rotation::Deg180 } else { Rotation::Deg0 },
-                direction: if p90 == wedge.min || p90 == wedge.max { Direction::ZPositive } else { Direction::ZNegative },
-                ..Default::default()
-            })
-        } else {
-            None
+    /// Splits a square off the tip of the fill where two steps meet at `joint`, returning the corner wedge at the joint
+    /// <br> and the wedge behind it, whose `[p1, p90, p2]` replace the joint in the outline.
+    /// <br> The two tile the square, so the outline from above is unchanged. `None` if the steps don't meet at a right angle.
+    fn joint_corner(&self, before: &[Point; 3], after: &[Point; 3]) -> Option<([Point; 3], [Point; 3])> {
+        let (a, joint, b) = (before[1], before[2], after[1]);
+        let (da, db) = ((a.x - joint.x, a.y - joint.y), (b.x - joint.x, b.y - joint.y));
+
+        // Exactly one axis changes towards each 90deg vertex.
+        let perpendicular = (da.0 == 0) != (da.1 == 0) && (db.0 == 0) != (db.1 == 0) && (da.0 == 0) != (db.0 == 0);
+        if !perpendicular {
+            return None;
         }
-    }
 
-    fn rectangle_brick(&self, rectangle: &Polygon) -> Brick {
-        let (size, position) = (rectangle.size, rectangle.position);
-        Brick {
-                asset_name_index: BrickAssets::MicroBrick.index() as u32,
-                color: rgb_to_brick(self.rgb),
-                size: Size::Procedural(size.x, size.y, 100),
-                material_index: 0,
-                position: (position.x, position.y, 100),
-                ..Default::default()
+        // Small enough to stay clear of the other side of the arc.
+        let side = (da.0.abs() + da.1.abs())
+            .min(db.0.abs() + db.1.abs())
+            .min(self.radius_gap as i32 / 4);
+        if side == 0 {
+            return None;
         }
+
+        let step = |p: Point, d: (i32, i32)| Point::new(p.x + d.0.signum() * side, p.y + d.1.signum() * side);
+        let (a, b) = (step(joint, da), step(joint, db));
+        let back = step(a, db);
+
+        Some(([a, joint, b], [a, back, b]))
+    }
+
+    fn wedge_bricks(&self, wedge: &Polygon) -> Vec<Brick> {
+        wedge_bricks(wedge, self.rgb, self.height, self.elevation)
+    }
+
+    fn rectangle_bricks(&self, rectangle: &Polygon) -> Vec<Brick> {
+        rectangle_bricks(rectangle, self.rgb, self.height, self.elevation)
     }
 
     fn build_wedges_and_rectangles(
@@ -178,14 +389,45 @@ impl WedgeArc {
             .iter()
             .zip([&mut inner_wedges, &mut outer_wedges].iter_mut().zip([&mut inner_points, &mut outer_points].iter_mut()))
         {
-            for vertex_group in arc.vertex_points() {
-                let wedge = Polygon::new(&vertex_group);
-                if let Some(brick) = self.wedge_brick(&wedge) {
-                    wedge_bricks.push(brick);
-                    debug.draw_outline(&vertex_group, self.rgb);
+            let steps: Vec<[Point; 3]> = arc.split_vertex_points(self.style.parts()).collect();
+            // The outline points replacing the start of the next step, if its joint was cornered.
+            let mut joint: Option<[Point; 3]> = None;
+
+            for (i, vertex_group) in steps.iter().enumerate() {
+                let wedge = Polygon::new(vertex_group);
+                let stack = self.wedge_bricks(&wedge);
+                if !stack.is_empty() {
+                    wedge_bricks.extend(stack);
+                    debug.draw_outline(vertex_group, self.rgb);
                 }
                 wedges.push(wedge);
-                vertices.extend(vertex_group);
+
+                match joint.take() {
+                    Some(points) => vertices.extend(points),
+                    None => vertices.push(vertex_group[0]),
+                }
+                vertices.push(vertex_group[1]);
+
+                let corner = match (self.style, steps.get(i + 1)) {
+                    (WedgeStyle::Corners, Some(next)) => self.joint_corner(vertex_group, next),
+                    _ => None,
+                };
+                match corner {
+                    Some((corner, back)) => {
+                        // The outer edge of the wall bulges out at the joints, the inner edge curves in.
+                        let asset = if arc.is_inner_arc { BrickAssets::MicroWedgeInnerCorner } else { BrickAssets::MicroWedgeOuterCorner };
+                        let corner_stack = corner_bricks(asset, &Polygon::new(&corner), self.rgb, self.height, self.elevation);
+                        let back_stack = self.wedge_bricks(&Polygon::new(&back));
+                        for (stack, triangle) in [(corner_stack, corner), (back_stack, back)] {
+                            if !stack.is_empty() {
+                                wedge_bricks.extend(stack);
+                                debug.draw_outline(&triangle, self.rgb);
+                            }
+                        }
+                        joint = Some(back);
+                    }
+                    None => vertices.push(vertex_group[2]),
+                }
             }
         }
 
@@ -200,8 +442,8 @@ impl WedgeArc {
         polygon.extend(&outer_points);
 
 
-        for rectangle in render_as_squares(polygon, debug) {
-            bricks.push(self.rectangle_brick(&rectangle)) 
+        for rectangle in render_as_squares_with(polygon, self.partition, debug) {
+            bricks.extend(self.rectangle_bricks(&rectangle))
         }
 
         for wedge in wedge_bricks {
@@ -210,6 +452,13 @@ impl WedgeArc {
 
     }
 
+    /// The largest gap, in micro-bricks, between the inner or outer steps and their circles.
+    pub fn deviation(&self) -> f32 {
+        let inner_radius = self.arc.radius.saturating_sub(self.radius_gap);
+        let parts = self.style.parts();
+        self.arc.split_deviation(parts).max(self.arc.clone().concentric(inner_radius).split_deviation(parts))
+    }
+
     /// Processes an arc into wedges and draws corner lines.
     pub fn compute(&mut self, debug: &mut DebugImage, mut bricks: &mut Vec<Brick>) {
 
@@ -227,7 +476,6 @@ impl WedgeArc {
 mod render_arc {
 
     use super::*;
-    use std::env;
     use image::RgbImage;
     use crate::{draw::colors::{BLACK, WHITE}, metadata::save::save_bricks};
 
@@ -250,19 +498,239 @@ mod render_arc {
                 centre: Point::new(500, 500),
                 is_inner_arc: false,
                 radius: 650,
+                precision: Precision::default(),
             }, 
-            radius_gap: 50
+            radius_gap: 50,
+            height: 100,
+            elevation: 0,
+            style: WedgeStyle::Corners,
+            partition: Partition::Greedy,
         };
 
         let mut bricks: Vec<Brick> = Vec::new();
 
         wedge_arc.compute(&mut debug, &mut bricks);
 
-        unsafe { env::set_var("NAME", "Arc"); }
+        save_bricks(bricks, "Arc", None).unwrap();
+
+        debug.save("circle.png").unwrap();
+
+    }
+}
+#[cfg(test)]
+mod arc_steps {
+
+    use super::*;
+
+    fn arc(radius: u32, begin_angle: f32, end_angle: f32, precision: Precision) -> Arc {
+        Arc { begin_angle, end_angle, centre: Point::new(0, 0), radius, is_inner_arc: false, precision }
+    }
+
+    #[test]
+    fn run() {
+        // A quarter of radius 100 within half a micro-brick: steps of 2 acos(0.995), about 0.2 radians.
+        let quarter = arc(100, 0.0, PI / 2.0, Precision::Tolerance(0.5));
+        assert_eq!(quarter.steps(), 8);
+        assert!((quarter.deviation() - 100.0 * (1.0 - (PI / 32.0).cos())).abs() < 1e-3);
+        assert!(quarter.deviation() <= 0.5);
+
+        // A radian of radius 300 in wedges of at most 30: ten steps of 0.1 radians.
+        let radian = arc(300, 1.0, 2.0, Precision::MaxWedgeLength(30));
+        assert_eq!(radian.steps(), 10);
+        assert!((radian.deviation() - 300.0 * (1.0 - 0.05f32.cos())).abs() < 1e-3);
+
+        // Wedge lengths divide the arc rounded down: the 158 micro-bricks of the quarter make five steps.
+        assert_eq!(arc(100, 0.0, PI / 2.0, Precision::default()).steps(), 5);
+
+        // Reversed ends span the same arc.
+        for forward in [quarter, radian] {
+            let reversed = arc(forward.radius, forward.end_angle, forward.begin_angle, forward.precision);
+            assert_eq!(reversed.steps(), forward.steps());
+            assert_eq!(reversed.deviation(), forward.deviation());
+            assert_eq!(reversed.vertex_points().collect::<Vec<_>>(), forward.vertex_points().collect::<Vec<_>>());
+        }
+
+        // Tolerances too fine for the f32 circle still step at least a micro-brick of arc.
+        assert!(arc(100, 0.0, PI / 2.0, Precision::Tolerance(1e-9)).steps() <= 158);
+    }
+}
 
-        save_bricks(bricks);
+#[cfg(test)]
+mod partition_arcs {
 
-        debug.save("circle.png");
+    use super::*;
+    use brickadia::save::Size;
+    use crate::draw::colors::BLACK;
+
+    /// The number of micro-bricks between the wedges, and the area they cover.
+    fn rectangles(arc: &Arc, radius_gap: u32, partition: Partition) -> (usize, u32) {
+        let mut wedge_arc = WedgeArc {
+            rgb: BLACK,
+            arc: arc.clone(),
+            radius_gap,
+            height: 100,
+            elevation: 0,
+            style: WedgeStyle::Simple,
+            partition,
+        };
+        let mut bricks = Vec::new();
+        wedge_arc.compute(&mut DebugImage::new(None, BLACK), &mut bricks);
 
+        bricks
+            .iter()
+            .filter(|brick| brick.asset_name_index == BrickAssets::MicroBrick.index() as u32)
+            .fold((0, 0), |(count, area), brick| match brick.size {
+                Size::Procedural(x, y, _) => (count + 1, area + x * y),
+                _ => (count, area),
+            })
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn run() {
+        let (mut greedy_total, mut minimum_total) = (0, 0);
+
+        for (radius, radius_gap, end_angle) in [(150, 30, SEMICIRCLE), (300, 40, 2.0), (200, 30, CIRCLE), (500, 40, 1.0)] {
+            let arc = Arc {
+                begin_angle: 0.3,
+                end_angle: 0.3 + end_angle,
+                centre: Point::new(radius as i32, radius as i32),
+                radius,
+                is_inner_arc: false,
+                precision: Precision::default(),
+            };
+            let (greedy, greedy_area) = rectangles(&arc, radius_gap, Partition::Greedy);
+            let (minimum, minimum_area) = rectangles(&arc, radius_gap, Partition::Minimum);
+
+            assert_eq!(minimum_area, greedy_area);
+            assert!(minimum <= greedy, "radius {}: {} minimum against {} greedy rectangles", radius, minimum, greedy);
+            greedy_total += greedy;
+            minimum_total += minimum;
+        }
+
+        assert!(minimum_total < greedy_total, "{} minimum against {} greedy rectangles in all", minimum_total, greedy_total);
+    }
+}
+
+#[cfg(test)]
+mod corner_wedges {
+
+    use std::collections::HashSet;
+    use strum::IntoEnumIterator;
+    use super::*;
+    use crate::draw::colors::BLACK;
+
+    fn bricks(style: WedgeStyle) -> Vec<Brick> {
+        let mut wedge_arc = WedgeArc {
+            rgb: BLACK,
+            arc: Arc {
+                begin_angle: 0.3,
+                end_angle: 0.3 + SEMICIRCLE,
+                centre: Point::new(0, 0),
+                radius: 300,
+                is_inner_arc: false,
+                precision: Precision::MaxWedgeLength(60),
+            },
+            radius_gap: 40,
+            height: 10,
+            elevation: 0,
+            style,
+            partition: Partition::Greedy,
+        };
+        let mut bricks = Vec::new();
+        wedge_arc.compute(&mut DebugImage::new(None, BLACK), &mut bricks);
+        bricks
+    }
+
+    /// The micro-bricks covered from above, taking wedges of every kind as the right-angled half of their box
+    /// <br> at the corner they're turned to. Coordinates are doubled to test the middle of each micro-brick.
+    fn footprint(bricks: &[Brick]) -> HashSet<(i32, i32)> {
+        let mut covered = HashSet::new();
+        for brick in bricks {
+            let Size::Procedural(width, height, _) = brick.size else { continue };
+            let (width, height) = (width as i32, height as i32);
+            let (left, top) = ((brick.position.0 - width) / 2, (brick.position.1 - height) / 2);
+            let (min, max) = ((2 * left, 2 * top), (2 * (left + width), 2 * (top + height)));
+
+            let right_angle = match BrickAssets::iter().nth(brick.asset_name_index as usize) {
+                Some(BrickAssets::MicroBrick) => None,
+                Some(BrickAssets::MicroWedge) => Some(match (brick.rotation, brick.direction) {
+                    (Rotation::Deg180, Direction::ZPositive) => max,
+                    (Rotation::Deg180, _) => (min.0, max.1),
+                    (_, Direction::ZPositive) => min,
+                    _ => (max.0, min.1),
+                }),
+                _ => Some(match brick.rotation {
+                    Rotation::Deg0 => max,
+                    Rotation::Deg90 => (min.0, max.1),
+                    Rotation::Deg180 => min,
+                    Rotation::Deg270 => (max.0, min.1),
+                }),
+            };
+
+            for x in left..left + width {
+                for y in top..top + height {
+                    let (px, py) = (2 * x + 1, 2 * y + 1);
+                    let inside = right_angle.is_none_or(|(cx, cy)| {
+                        (px - cx).abs() * height + (py - cy).abs() * width <= 2 * width * height
+                    });
+                    if inside {
+                        covered.insert((x, y));
+                    }
+                }
+            }
+        }
+        covered
+    }
+
+    /// The micro-bricks between the ends of the arc covered outside its ring, or left uncovered inside it.
+    fn error(bricks: &[Brick]) -> usize {
+        let covered = footprint(bricks);
+        let (inner, outer) = (260.0, 300.0);
+        (-310..310)
+            .flat_map(|x| (-310..310).map(move |y| (x, y)))
+            .filter(|&(x, y)| {
+                let (cx, cy) = (x as f32 + 0.5, y as f32 + 0.5);
+                let angle = cy.atan2(cx).rem_euclid(CIRCLE);
+                let in_ring = (inner..=outer).contains(&cx.hypot(cy));
+                (0.35..0.25 + SEMICIRCLE).contains(&angle) && in_ring != covered.contains(&(x, y))
+            })
+            .count()
+    }
+
+    #[test]
+    fn run() {
+        let is_corner = |brick: &Brick, asset: BrickAssets| brick.asset_name_index == asset.index() as u32;
+        let simple = bricks(WedgeStyle::Simple);
+        let corners = bricks(WedgeStyle::Corners);
+
+        assert!(!simple.iter().any(|b| is_corner(b, BrickAssets::MicroWedgeOuterCorner) || is_corner(b, BrickAssets::MicroWedgeInnerCorner)));
+
+        // Stepping through the middle of each step keeps the outline from above closer to the ring.
+        let (simple_error, corners_error) = (error(&simple), error(&corners));
+        assert!(corners_error * 2 < simple_error, "{} micro-bricks off the ring with corners, {} without", corners_error, simple_error);
+
+        // The tips of the outer corners point away from the centre, those of the inner corners towards it.
+        for (asset, outwards) in [(BrickAssets::MicroWedgeOuterCorner, true), (BrickAssets::MicroWedgeInnerCorner, false)] {
+            let placed: Vec<&Brick> = corners.iter().filter(|b| is_corner(b, asset)).collect();
+            assert!(!placed.is_empty(), "no {:?}", asset);
+
+            for brick in placed {
+                let Size::Procedural(x, y, _) = brick.size else { panic!("unsized corner") };
+                assert_eq!(x, y, "corners are cut from squares");
+                assert_eq!(brick.direction, Direction::ZPositive);
+
+                let (dx, dy) = (brick.position.0, brick.position.1);
+                let tip = match brick.rotation {
+                    Rotation::Deg0 => (1, 1),
+                    Rotation::Deg90 => (-1, 1),
+                    Rotation::Deg180 => (-1, -1),
+                    Rotation::Deg270 => (1, -1),
+                };
+                let away = (dx.signum(), dy.signum());
+                if away.0 != 0 && away.1 != 0 {
+                    assert_eq!(tip == away, outwards, "{:?} at {:?} turned {:?}", asset, brick.position, brick.rotation);
+                }
+            }
+        }
+    }
+}
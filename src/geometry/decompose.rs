@@ -1,10 +1,36 @@
 
 use crate::draw::draw::DebugImage;
 use crate::utils::points::Point;
+use super::partition::minimum_rectangles;
 use super::polygon::Polygon;
-use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumString};
+
+/// Rectangles and walls reaching this size along any axis are split into smaller bricks.
+pub const MAX_LENGTH: i32 = 1000;
+
+/// How `render_as_squares_with` partitions a polygon into rectangles.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumString, Serialize, Deserialize)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum Partition {
+    /// Expands each rectangle right, then down, from the first free cell of each row. Fast.
+    #[default]
+    Greedy,
+    /// Cuts the polygon along the most non-crossing chords between its concave corners.
+    /// <br> The fewest rectangles for polygons without holes, but slower.
+    Minimum,
+}
+
+/// A rectangle of grid cells, as `(column, row, width, height)`.
+pub type CellRectangle = (usize, usize, usize, usize);
 
 pub fn render_as_squares(polygon: Polygon, debug: &mut DebugImage) -> Vec<Polygon> {
+    render_as_squares_with(polygon, Partition::Greedy, debug)
+}
+
+/// Decomposes the polygon into grid-aligned rectangles with the chosen `partition`.
+pub fn render_as_squares_with(polygon: Polygon, partition: Partition, debug: &mut DebugImage) -> Vec<Polygon> {
 
     //println!("Polygon {:?}", polygon.points);
 
@@ -14,78 +40,36 @@ pub fn render_as_squares(polygon: Polygon, debug: &mut DebugImage) -> Vec<Polygo
     let min_x = polygon.min.x;
     let min_y = polygon.min.y;
 
-    // Create a grid to track which points are inside the polygon
-    let column_count = (polygon.size.x + 1) as usize;
-    let row_count = (polygon.size.y + 1) as usize;
-    let mut grid = vec![vec![false; column_count]; row_count];
-
-    // Mark grid cells that are inside the polygon
-    grid.par_iter_mut().enumerate().for_each(|(row, row_data)| {
-        let y_coord = min_y + row as i32;
-        for column in 0..column_count {
-            let x_coord = min_x + column as i32;
-            let point = Point { x: x_coord, y: y_coord };
-            if polygon.contains(&point) {
-                row_data[column] = true;
-            }
-        }
-    });
+    // The runs of cells inside the polygon on each row, as columns from the left of the bounding box
+    let spans: Vec<Vec<(usize, usize)>> = polygon
+        .row_spans()
+        .into_iter()
+        .map(|row| row.into_iter().map(|(begin, end)| ((begin - min_x) as usize, (end - min_x) as usize)).collect())
+        .collect();
 
-    // Track processed cells to avoid duplicates
-    let mut processed = vec![vec![false; column_count]; row_count];
+    let cells = match partition {
+        Partition::Greedy => greedy_rectangles(spans),
+        Partition::Minimum => minimum_rectangles(&spans),
+    };
 
-    // Generate merged rectangles
-    for row in 0..row_count {
-        for column in 0..column_count {
-            // Skip cells outside the polygon or already processed
-            if grid[row][column] && !processed[row][column] {
+    for (column, row, width, height) in cells {
 
-                // Rectangle
-                let mut width = 0;
-                let mut height = 0;
+        // Position and Dimensions
+        let (x, y) = (min_x + column as i32, min_y + row as i32);
+        let (w, h) = (width as i32, height as i32);
 
-                // Expand horizontally (x-axis)
-                while column + width < column_count && grid[row][column + width] && !processed[row][column + width] {
-                    width += 1;
-                }
+        let tl = Point { x, y };
+        let br = Point { x: x + w, y: y + h };
+        let tr = Point { x: x + w, y };
+        let bl = Point { x, y: y + h };
 
-                // Expand vertically (y-axis) while maintaining the horizontal width
-                'vertical: while row + height < row_count {
-                    for dx in 0..width {
-                        if !grid[row + height][column + dx] || processed[row + height][column + dx] {
-                            // Expansion stops as soon as any column in the row fails the condition.
-                            break 'vertical;
-                        }
-                    }
-                    height += 1;
-                }
-
-                // Mark the rectangle as processed during expansion
-                for dy in 0..height {
-                    for dx in 0..width {
-                        processed[row + dy][column + dx] = true;
-                    }
-                }
-
-                // Position and Dimensions
-                let (x, y) = (min_x + column as i32, min_y + row as i32);
-                let (w, h) = (width as i32, height as i32);
-
-                let tl = Point { x, y };
-                let br = Point { x: x + w, y: y + h };
-                let tr = Point { x: x + w, y };
-                let bl = Point { x, y: y + h };
-
-                let rectangle = Polygon::new(&[tl, tr, br, bl]);
-                
-                const MAX_LENGTH: i32 = 1000;
-                if width as i32 >= MAX_LENGTH || height as i32 >= MAX_LENGTH {
-                    let mut divided_rectangles = halve_rectangle(&rectangle, MAX_LENGTH);
-                    rectangles.append(&mut divided_rectangles);
-                } else {
-                    rectangles.push(rectangle);
-                }
-            }
+        let rectangle = Polygon::new(&[tl, tr, br, bl]);
+        
+        if w >= MAX_LENGTH || h >= MAX_LENGTH {
+            let mut divided_rectangles = halve_rectangle(&rectangle, MAX_LENGTH);
+            rectangles.append(&mut divided_rectangles);
+        } else {
+            rectangles.push(rectangle);
         }
     }
 
@@ -98,6 +82,45 @@ pub fn render_as_squares(polygon: Polygon, debug: &mut DebugImage) -> Vec<Polygo
     rectangles
 }
 
+/// Merges the spans row by row, expanding each rectangle right and then down.
+/// <br> The first free cell of a row starts a rectangle as wide as its span,
+/// <br> which grows down while a single free span of the next row covers it.
+fn greedy_rectangles(mut free: Vec<Vec<(usize, usize)>>) -> Vec<CellRectangle> {
+
+    let mut rectangles = Vec::new();
+
+    for row in 0..free.len() {
+        while let Some(&(begin, end)) = free[row].first() {
+            free[row].remove(0);
+
+            // Expand vertically (y-axis) while maintaining the horizontal width
+            let mut height = 1;
+            while let Some(below) = free.get_mut(row + height) {
+                if !take_span(below, begin, end) {
+                    break;
+                }
+                height += 1;
+            }
+
+            rectangles.push((begin, row, end - begin, height));
+        }
+    }
+    rectangles
+}
+
+/// Removes `begin..end` from the sorted, disjoint spans if a single span covers it.
+fn take_span(spans: &mut Vec<(usize, usize)>, begin: usize, end: usize) -> bool {
+    let i = spans.partition_point(|&(_, span_end)| span_end <= begin);
+    match spans.get(i) {
+        Some(&(span_begin, span_end)) if span_begin <= begin && end <= span_end => {
+            let remainder = [(span_begin, begin), (end, span_end)];
+            spans.splice(i..=i, remainder.into_iter().filter(|&(b, e)| b < e));
+            true
+        }
+        _ => false,
+    }
+}
+
 /// Splits a large rectangle into smaller rectangles if its width or height exceeds `max_length`.
 fn halve_rectangle(rectangle: &Polygon, max_size: i32) -> Vec<Polygon> {
 
@@ -159,4 +182,140 @@ fn halve_rectangle(rectangle: &Polygon, max_size: i32) -> Vec<Polygon> {
     }
 
     rectangle_halfs
-}
\ No newline at end of file
+}
+/// Splits a `height` into layers shorter than `MAX_LENGTH`, stacked upwards from `elevation`.
+/// <br> Returns the `(height, elevation)` of each layer, which meet without gaps or overlaps.
+pub fn stack_layers(height: u32, elevation: i32) -> Vec<(u32, i32)> {
+    let count = height.div_ceil(MAX_LENGTH as u32 - 1).max(1);
+    let (layer, remainder) = (height / count, height % count);
+
+    let mut bottom = elevation;
+    (0..count)
+        .map(|i| {
+            let layer = layer + u32::from(i < remainder);
+            let layer_elevation = bottom;
+            bottom += layer as i32;
+            (layer, layer_elevation)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod stack_walls {
+
+    use super::*;
+
+    #[test]
+    fn run() {
+        assert_eq!(stack_layers(100, 5), vec![(100, 5)]);
+
+        for height in [999, 1000, 1998, 2500, 10_001] {
+            let layers = stack_layers(height, -20);
+            assert!(layers.iter().all(|&(layer, _)| (layer as i32) < MAX_LENGTH));
+            assert_eq!(layers.iter().map(|&(layer, _)| layer).sum::<u32>(), height);
+
+            // Each layer starts where the one below it ends.
+            let mut bottom = -20;
+            for (layer, elevation) in layers {
+                assert_eq!(elevation, bottom);
+                bottom += layer as i32;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod scanline {
+
+    use super::*;
+    use crate::geometry::arc::{Arc, Precision};
+
+    /// The grid decomposition the spans replaced, testing every cell with `contains`.
+    fn grid_rectangles(polygon: &Polygon) -> Vec<CellRectangle> {
+        let row_count = (polygon.size.y + 1) as usize;
+        let column_count = (polygon.size.x + 1) as usize;
+        let grid: Vec<Vec<bool>> = (0..row_count)
+            .map(|row| {
+                (0..column_count)
+                    .map(|column| polygon.contains(&Point::new(polygon.min.x + column as i32, polygon.min.y + row as i32)))
+                    .collect()
+            })
+            .collect();
+
+        let mut processed = vec![vec![false; column_count]; row_count];
+        let mut rectangles = Vec::new();
+        for row in 0..row_count {
+            for column in 0..column_count {
+                if grid[row][column] && !processed[row][column] {
+                    let mut width = 0;
+                    while column + width < column_count && grid[row][column + width] && !processed[row][column + width] {
+                        width += 1;
+                    }
+                    let mut height = 0;
+                    while row + height < row_count
+                        && (column..column + width).all(|c| grid[row + height][c] && !processed[row + height][c])
+                    {
+                        height += 1;
+                    }
+                    for line in &mut processed[row..row + height] {
+                        line[column..column + width].fill(true);
+                    }
+                    rectangles.push((column, row, width, height));
+                }
+            }
+        }
+        rectangles
+    }
+
+    #[test]
+    fn run() {
+        let ring = |radius, begin_angle: f32, end_angle: f32| {
+            let mut arc = Arc {
+                begin_angle,
+                end_angle,
+                centre: Point::new(-40, 75),
+                radius,
+                is_inner_arc: false,
+                precision: Precision::MaxWedgeLength(12),
+            };
+            let mut points: Vec<Point> = arc.vertex_points().map(|[p1, _, _]| p1).collect();
+            let inner = arc.concentric(radius - 25);
+            points.extend(inner.vertex_points().rev().map(|[p1, _, _]| p1));
+            Polygon::new(&points)
+        };
+
+        let polygons = [
+            Polygon::from_tuples(&[(0, 0), (40, 0), (40, 30), (0, 30)]),
+            Polygon::from_tuples(&[(5, -10), (60, 25), (-20, 47)]),
+            Polygon::from_tuples(&[(0, 0), (50, 0), (50, 50), (30, 50), (30, 20), (20, 20), (20, 50), (0, 50)]),
+            Polygon::from_tuples(&[(0, 0), (30, 10), (60, 0), (50, 30), (60, 60), (30, 50), (0, 60), (10, 30)]),
+            Polygon::from_tuples(&[(0, 0), (20, 0), (20, 20), (40, 20), (40, 40), (20, 40), (20, 20), (0, 20)]),
+            ring(90, 0.3, 2.9),
+            ring(120, 0.0, std::f32::consts::TAU),
+        ];
+
+        for polygon in &polygons {
+            // Every span holds exactly the points `contains` holds.
+            for (row, spans) in polygon.row_spans().iter().enumerate() {
+                let y = polygon.min.y + row as i32;
+                for x in polygon.min.x - 1..=polygon.max.x + 1 {
+                    let in_span = spans.iter().any(|&(begin, end)| (begin..end).contains(&x));
+                    assert_eq!(in_span, polygon.contains(&Point::new(x, y)), "({}, {}) of {:?}", x, y, polygon.points);
+                }
+            }
+
+            let mut debug = DebugImage::new(None, crate::draw::colors::BLACK);
+            let spans = render_as_squares(polygon.clone(), &mut debug);
+            let grid: Vec<Polygon> = grid_rectangles(polygon)
+                .into_iter()
+                .map(|(column, row, width, height)| {
+                    let (x, y) = (polygon.min.x + column as i32, polygon.min.y + row as i32);
+                    let (w, h) = (width as i32, height as i32);
+                    Polygon::from_tuples(&[(x, y), (x + w, y), (x + w, y + h), (x, y + h)])
+                })
+                .collect();
+
+            assert_eq!(spans.iter().map(|p| &p.points).collect::<Vec<_>>(), grid.iter().map(|p| &p.points).collect::<Vec<_>>());
+        }
+    }
+}
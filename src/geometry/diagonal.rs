@@ -1,28 +1,39 @@
 use crate::utils::points::Point;
 
-/// Approximates a diagonal line with right-angled triangles above and below the line.
-pub fn diagonal_as_triangles(from: Point, to: Point) -> Vec<Vec<Point>> {
-    let mut triangles = Vec::new();
-    let (mut x, mut y) = (from.x, from.y);
-    let (dx, dy) = ((to.x - from.x).abs(), (to.y - from.y).abs());
-    let (step_x, step_y) = (if to.x > from.x { 1 } else { -1 }, if to.y > from.y { 1 } else { -1 });
-    let (mut error, primary, secondary) = if dx > dy { (0, dx, dy) } else { (0, dy, dx) };
+/// Approximates a diagonal line with `steps` right-angled triangles along it.
+/// <br> The right angle of each triangle lies on the same side of the line as `inside`.
+pub fn diagonal_as_triangles(from: Point, to: Point, steps: usize, inside: Point) -> Vec<[Point; 3]> {
+    let steps = steps.max(1);
+    let side = |p: &Point| {
+        ((to.x - from.x) as i64 * (p.y - from.y) as i64 - (to.y - from.y) as i64 * (p.x - from.x) as i64).signum()
+    };
+    let inside = side(&inside);
 
-    while (x, y) != (to.x, to.y) {
-        let next_x = x + if dx > dy { step_x } else { 0 };
-        let next_y = y + if dx > dy { 0 } else { step_y };
+    let step = |i: usize| {
+        let t = i as f32 / steps as f32;
+        Point::from_f32((
+            from.x as f32 + (to.x - from.x) as f32 * t,
+            from.y as f32 + (to.y - from.y) as f32 * t,
+        ))
+    };
 
-        triangles.push(vec![Point { x, y }, Point { x: next_x, y: y + step_y }, Point { x: next_x, y: next_y }]);
-        triangles.push(vec![Point { x, y }, Point { x: x + step_x, y }, Point { x: next_x, y: y + step_y }]);
+    (0..steps)
+        .map(|i| {
+            let (p1, p2) = (step(i), step(i + 1));
 
-        error += secondary;
-        if error >= primary {
-            if dx > dy { /* no-op */ };
-            error -= primary;
-        }
-        x = next_x;
-        y = next_y;
-    }
+            // The coordinates of the 90deg vertex
+            let p90 = if side(&Point::new(p1.x, p2.y)) == inside {
+                Point::new(p1.x, p2.y)
+            } else {
+                Point::new(p2.x, p1.y)
+            };
 
-    triangles
-}
\ No newline at end of file
+            [p1, p90, p2]
+        })
+        .collect()
+}
+
+/// Whether a line strays at most `tolerance` units from the `x` or `y` axis over its whole length.
+pub fn is_near_axis(from: Point, to: Point, tolerance: i32) -> bool {
+    (to.x - from.x).abs() <= tolerance || (to.y - from.y).abs() <= tolerance
+}
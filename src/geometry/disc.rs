@@ -0,0 +1,65 @@
+use crate::utils::points::Point;
+use super::arc::{Arc, Precision, CIRCLE};
+use super::polygon::Polygon;
+
+/// A circle stepped onto the micro-brick grid, such as the outline of a theta maze's floor and ceiling.
+pub struct Disc {
+    pub centre: Point,
+    pub radius: u32,
+    /// How closely the edge follows the circle.
+    pub precision: Precision,
+}
+
+impl Disc {
+    /// The polygon through the steps of the circle.
+    pub fn polygon(&self) -> Polygon {
+        let circle = Arc {
+            begin_angle: 0.0,
+            end_angle: CIRCLE,
+            centre: self.centre,
+            radius: self.radius,
+            is_inner_arc: false,
+            precision: self.precision,
+        };
+        let points: Vec<Point> = circle.vertex_points().map(|[p1, _, _]| p1).collect();
+        Polygon::new(&points)
+    }
+}
+
+#[cfg(test)]
+mod fill_disc {
+
+    use super::*;
+    use brickadia::save::Size;
+    use std::f32::consts::PI;
+    use crate::draw::colors::BLACK;
+    use crate::draw::draw::DebugImage;
+    use crate::geometry::decompose::Partition;
+    use crate::maze::Slab;
+    use crate::metadata::assets::BrickMaterials;
+
+    #[test]
+    fn run() {
+        let disc = Disc {
+            centre: Point::new(0, 0),
+            radius: 300,
+            precision: Precision::Tolerance(0.5),
+        };
+        let slab = Slab { material: BrickMaterials::Metallic, ..Slab::default() };
+        let mut bricks = Vec::new();
+        slab.compute(&disc.polygon(), Partition::Minimum, -10, &mut DebugImage::new(None, BLACK), &mut bricks);
+
+        let area: u32 = bricks
+            .iter()
+            .map(|brick| match brick.size {
+                Size::Procedural(x, y, _) => x * y,
+                _ => 0,
+            })
+            .sum();
+        let circle = PI * 300.0 * 300.0;
+        assert!((area as f32 - circle).abs() / circle < 0.02, "area {} of {}", area, circle);
+
+        assert!(bricks.iter().all(|brick| brick.material_index == BrickMaterials::Metallic.index() as u32));
+        assert!(bricks.iter().all(|brick| brick.position.2 == -10));
+    }
+}
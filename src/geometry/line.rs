@@ -0,0 +1,128 @@
+use brickadia::save::Brick;
+use image::Rgb;
+use crate::draw::draw::DebugImage;
+use crate::utils::points::Point;
+use super::arc::{rectangle_bricks, wedge_bricks};
+use super::decompose::render_as_squares;
+use super::diagonal::{diagonal_as_triangles, is_near_axis};
+use super::polygon::Polygon;
+
+/// Lines straying at most this far from an axis are built without wedges.
+pub const AXIS_TOLERANCE: i32 = 2;
+
+/// A straight line segment of a given width, built from `MicroBrick` and `MicroWedge` bricks.
+pub struct ThickLine {
+    pub rgb: Rgb<u8>,
+    pub from: Point,
+    pub to: Point,
+    /// Thickness of the line, centred on the segment.
+    pub width: u32,
+    /// Vertical size of the bricks.
+    pub height: u32,
+    /// Height of the bottom of the bricks above the ground.
+    pub elevation: i32,
+}
+
+impl ThickLine {
+
+    /// The corners on either side of `from` and `to`, as `[a1, a2, b2, b1]`.
+    pub fn corners(&self) -> [Point; 4] {
+        let (dx, dy) = ((self.to.x - self.from.x) as f32, (self.to.y - self.from.y) as f32);
+        let length = dx.hypot(dy);
+        let half = self.width as f32 / 2.0;
+        let (nx, ny) = (-dy / length * half, dx / length * half);
+
+        let offset = |p: Point, sign: f32| {
+            Point::from_f32((p.x as f32 + sign * nx, p.y as f32 + sign * ny))
+        };
+
+        [offset(self.from, -1.0), offset(self.to, -1.0), offset(self.to, 1.0), offset(self.from, 1.0)]
+    }
+
+    /// The fewest wedge steps along each edge that keep every wedge within half the width of the line.
+    fn steps(&self) -> usize {
+        let (dx, dy) = ((self.to.x - self.from.x).abs() as f32, (self.to.y - self.from.y).abs() as f32);
+        (2.0 * dx * dy / (dx.hypot(dy) * self.width as f32)).ceil() as usize
+    }
+
+    /// Processes the line into bricks.
+    /// <br> Lines close to an axis are a single stack of micro-bricks, diagonal lines are stepped with wedges.
+    pub fn compute(&self, debug: &mut DebugImage, bricks: &mut Vec<Brick>) {
+        if self.from == self.to || self.width == 0 {
+            return;
+        }
+
+        let [a1, a2, b2, b1] = self.corners();
+
+        if is_near_axis(self.from, self.to, AXIS_TOLERANCE) {
+            let bounds = Polygon::new(&[a1, a2, b2, b1]);
+            let (min, max) = (bounds.min, bounds.max);
+            let rectangle = Polygon::new(&[min, Point::new(max.x, min.y), max, Point::new(min.x, max.y)]);
+            for rectangle in render_as_squares(rectangle, debug) {
+                bricks.extend(rectangle_bricks(&rectangle, self.rgb, self.height, self.elevation));
+            }
+            return;
+        }
+
+        let steps = self.steps();
+        let edge_a = diagonal_as_triangles(a1, a2, steps, b1);
+        let edge_b = diagonal_as_triangles(b1, b2, steps, a1);
+
+        // The stepped outline between the two edges, filled with rectangles.
+        let mut polygon = Polygon::new(&[]);
+        for [p1, p90, p2] in &edge_a {
+            polygon.extend(&[*p1, *p90, *p2]);
+        }
+        for [p1, p90, p2] in edge_b.iter().rev() {
+            polygon.extend(&[*p2, *p90, *p1]);
+        }
+
+        for rectangle in render_as_squares(polygon, debug) {
+            bricks.extend(rectangle_bricks(&rectangle, self.rgb, self.height, self.elevation));
+        }
+
+        for triangle in edge_a.iter().chain(edge_b.iter()) {
+            let stack = wedge_bricks(&Polygon::new(triangle), self.rgb, self.height, self.elevation);
+            if !stack.is_empty() {
+                bricks.extend(stack);
+                debug.draw_outline(triangle, self.rgb);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod render_line {
+
+    use super::*;
+    use crate::draw::colors::BLACK;
+    use crate::metadata::assets::BrickAssets;
+
+    fn line(from: Point, to: Point) -> Vec<Brick> {
+        let mut debug = DebugImage::new(None, BLACK);
+        let mut bricks = Vec::new();
+        ThickLine { rgb: BLACK, from, to, width: 50, height: 100, elevation: 0 }.compute(&mut debug, &mut bricks);
+        bricks
+    }
+
+    #[test]
+    fn run() {
+        let wedges = |bricks: &[Brick]| {
+            bricks.iter().filter(|b| b.asset_name_index == BrickAssets::MicroWedge.index() as u32).count()
+        };
+
+        // Axis aligned lines are a single micro-brick.
+        let straight = line(Point::new(0, 0), Point::new(0, 400));
+        assert_eq!(straight.len(), 1);
+
+        // Diagonals step along both edges with the same number of wedges.
+        let diagonal = line(Point::new(0, 0), Point::new(300, 300));
+        assert!(wedges(&diagonal) > 0);
+        assert_eq!(wedges(&diagonal) % 2, 0);
+
+        // Reversing the line builds the same number of bricks.
+        assert_eq!(diagonal.len(), line(Point::new(300, 300), Point::new(0, 0)).len());
+
+        assert!(line(Point::new(10, 10), Point::new(10, 10)).is_empty());
+    }
+}
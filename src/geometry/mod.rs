@@ -1,4 +1,7 @@
 pub mod arc;
 pub mod decompose;
 pub mod polygon;
-pub mod diagonal;
\ No newline at end of file
+pub mod diagonal;
+pub mod disc;
+pub mod line;
+pub mod partition;
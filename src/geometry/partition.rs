@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use super::decompose::CellRectangle;
+
+/// A cut along a grid line, from one lattice vertex `from` to another `to`.
+#[derive(Clone, Copy, Debug)]
+struct Chord {
+    line: isize,
+    from: isize,
+    to: isize,
+}
+
+impl Chord {
+    /// Whether a horizontal and a vertical chord cross or touch.
+    fn meets(horizontal: &Chord, vertical: &Chord) -> bool {
+        (horizontal.from..=horizontal.to).contains(&vertical.line) && (vertical.from..=vertical.to).contains(&horizontal.line)
+    }
+}
+
+/// The cells inside the polygon, as the sorted runs of columns inside it on each row.
+/// <br> Vertex `(x, y)` is the top left corner of the cell in `column` x and `row` y.
+struct Cells<'a> {
+    spans: &'a [Vec<(usize, usize)>],
+    rows: isize,
+}
+
+impl Cells<'_> {
+    fn inside(&self, row: isize, column: isize) -> bool {
+        if !(0..self.rows).contains(&row) || column < 0 {
+            return false;
+        }
+        let (spans, column) = (&self.spans[row as usize], column as usize);
+        let i = spans.partition_point(|&(_, end)| end <= column);
+        spans.get(i).is_some_and(|&(begin, _)| begin <= column)
+    }
+
+    /// The vertices on line `y` where a span of the row above or below it begins or ends, from left to right.
+    /// <br> Only these can be concave corners.
+    fn span_ends(&self, y: isize) -> Vec<isize> {
+        let mut ends: Vec<isize> = [y - 1, y]
+            .into_iter()
+            .filter(|row| (0..self.rows).contains(row))
+            .flat_map(|row| self.spans[row as usize].iter().flat_map(|&(begin, end)| [begin as isize, end as isize]))
+            .collect();
+        ends.sort_unstable();
+        ends.dedup();
+        ends
+    }
+
+    /// The directions of the horizontal and vertical edges leading into the polygon from a concave corner.
+    /// <br> `None` unless exactly three of the four cells around the vertex are inside.
+    fn reflex(&self, x: isize, y: isize) -> Option<(isize, isize)> {
+        let around = [
+            self.inside(y - 1, x - 1),
+            self.inside(y - 1, x),
+            self.inside(y, x - 1),
+            self.inside(y, x),
+        ];
+        match around {
+            [false, true, true, true] => Some((1, 1)),
+            [true, false, true, true] => Some((-1, 1)),
+            [true, true, false, true] => Some((1, -1)),
+            [true, true, true, false] => Some((-1, -1)),
+            _ => None,
+        }
+    }
+
+    /// Whether the edge from vertex `(x, y)` to `(x + dx, y)` has the polygon on both sides.
+    fn horizontal_inside(&self, x: isize, y: isize, dx: isize) -> bool {
+        let column = if dx > 0 { x } else { x - 1 };
+        self.inside(y - 1, column) && self.inside(y, column)
+    }
+
+    /// Whether the edge from vertex `(x, y)` to `(x, y + dy)` has the polygon on both sides.
+    fn vertical_inside(&self, x: isize, y: isize, dy: isize) -> bool {
+        let row = if dy > 0 { y } else { y - 1 };
+        self.inside(row, x - 1) && self.inside(row, x)
+    }
+}
+
+/// Partitions the spans of each row into the fewest rectangles, for polygons without holes.
+/// <br> Cuts along the largest set of non-crossing chords between concave corners,
+/// <br> then cuts horizontally from every concave corner left over.
+/// <br> Works on the spans and the cuts alone, so memory grows with the outline rather than the bounding box.
+pub fn minimum_rectangles(spans: &[Vec<(usize, usize)>]) -> Vec<CellRectangle> {
+    let cells = Cells { spans, rows: spans.len() as isize };
+
+    let reflex: Vec<(isize, isize, isize, isize)> = (0..=cells.rows)
+        .flat_map(|y| cells.span_ends(y).into_iter().map(move |x| (x, y)))
+        .filter_map(|(x, y)| cells.reflex(x, y).map(|(dx, dy)| (x, y, dx, dy)))
+        .collect();
+
+    // Chords run between two concave corners facing each other, found from their left or top ends.
+    let mut horizontal = Vec::new();
+    let mut vertical = Vec::new();
+    for &(x, y, dx, dy) in &reflex {
+        if dx > 0 {
+            let mut end = x;
+            while cells.horizontal_inside(end, y, 1) {
+                end += 1;
+            }
+            if end > x && cells.reflex(end, y).is_some() {
+                horizontal.push(Chord { line: y, from: x, to: end });
+            }
+        }
+        if dy > 0 {
+            let mut end = y;
+            while cells.vertical_inside(x, end, 1) {
+                end += 1;
+            }
+            if end > y && cells.reflex(x, end).is_some() {
+                vertical.push(Chord { line: x, from: y, to: end });
+            }
+        }
+    }
+
+    let (horizontal, vertical) = independent_chords(&horizontal, &vertical);
+
+    // The columns cut off from the row below each row, and the vertex lines cut through each row.
+    let rows = spans.len();
+    let mut cut_below: Vec<Vec<(isize, isize)>> = vec![Vec::new(); rows];
+    let mut cut_right: Vec<Vec<isize>> = vec![Vec::new(); rows];
+    let mut resolved = HashSet::new();
+
+    for chord in &horizontal {
+        cut_below[chord.line as usize - 1].push((chord.from, chord.to));
+        resolved.extend([(chord.from, chord.line), (chord.to, chord.line)]);
+    }
+    for chord in &vertical {
+        for row in chord.from..chord.to {
+            cut_right[row as usize].push(chord.line);
+        }
+        resolved.extend([(chord.line, chord.from), (chord.line, chord.to)]);
+    }
+    for lines in &mut cut_right {
+        lines.sort_unstable();
+    }
+
+    // Every other concave corner is cut horizontally, up to the boundary or a vertical cut crossing the line.
+    let crosses = |x: isize, y: isize| {
+        y >= 1 && y < cells.rows
+            && cut_right[y as usize - 1].binary_search(&x).is_ok()
+            && cut_right[y as usize].binary_search(&x).is_ok()
+    };
+    for &(x, y, dx, _) in &reflex {
+        if resolved.contains(&(x, y)) {
+            continue;
+        }
+        let mut at = x;
+        while cells.horizontal_inside(at, y, dx) {
+            at += dx;
+            if crosses(at, y) {
+                break;
+            }
+        }
+        if at != x {
+            cut_below[y as usize - 1].push((x.min(at), x.max(at)));
+        }
+    }
+
+    // Every region left between the cuts is a rectangle. Each span split at the vertical cuts
+    // continues the rectangle above it, unless that one spans other columns or is cut off.
+    let mut rectangles: Vec<CellRectangle> = Vec::new();
+    let mut above: HashMap<(usize, usize), usize> = HashMap::new();
+    for (row, row_spans) in spans.iter().enumerate() {
+        let mut current = HashMap::new();
+        for &(begin, end) in row_spans {
+            let lines = cut_right[row].iter().map(|&x| x as usize).filter(|&x| begin < x && x < end);
+            let bounds: Vec<usize> = std::iter::once(begin).chain(lines).chain(std::iter::once(end)).collect();
+
+            for segment in bounds.windows(2) {
+                let (begin, end) = (segment[0], segment[1]);
+                let cut_off = |&(from, to): &(isize, isize)| from < end as isize && (begin as isize) < to;
+                let index = match above.get(&(begin, end)) {
+                    Some(&index) if !cut_below[row - 1].iter().any(cut_off) => {
+                        rectangles[index].3 += 1;
+                        index
+                    }
+                    _ => {
+                        rectangles.push((begin, row, end - begin, 1));
+                        rectangles.len() - 1
+                    }
+                };
+                current.insert((begin, end), index);
+            }
+        }
+        above = current;
+    }
+    rectangles
+}
+
+/// The largest set of horizontal and vertical chords where no two meet.
+/// <br> By König's theorem, the complement of a minimum vertex cover of the bipartite crossing graph.
+fn independent_chords(horizontal: &[Chord], vertical: &[Chord]) -> (Vec<Chord>, Vec<Chord>) {
+    let crossings: Vec<Vec<usize>> = horizontal
+        .iter()
+        .map(|h| (0..vertical.len()).filter(|&v| Chord::meets(h, &vertical[v])).collect())
+        .collect();
+
+    // Maximum matching by augmenting paths.
+    let mut matched_vertical: Vec<Option<usize>> = vec![None; vertical.len()];
+    let mut matched_horizontal: Vec<Option<usize>> = vec![None; horizontal.len()];
+
+    fn augment(h: usize, crossings: &[Vec<usize>], seen: &mut [bool], matched_vertical: &mut [Option<usize>]) -> bool {
+        for &v in &crossings[h] {
+            if !seen[v] {
+                seen[v] = true;
+                if matched_vertical[v].is_none_or(|other| augment(other, crossings, seen, matched_vertical)) {
+                    matched_vertical[v] = Some(h);
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    for h in 0..horizontal.len() {
+        let mut seen = vec![false; vertical.len()];
+        augment(h, &crossings, &mut seen, &mut matched_vertical);
+    }
+    for (v, h) in matched_vertical.iter().enumerate() {
+        if let Some(h) = *h {
+            matched_horizontal[h] = Some(v);
+        }
+    }
+
+    // Chords reachable by alternating paths from the unmatched horizontal chords.
+    let mut reached_horizontal = vec![false; horizontal.len()];
+    let mut reached_vertical = vec![false; vertical.len()];
+    let mut queue: VecDeque<usize> = (0..horizontal.len()).filter(|&h| matched_horizontal[h].is_none()).collect();
+    for &h in &queue {
+        reached_horizontal[h] = true;
+    }
+    while let Some(h) = queue.pop_front() {
+        for &v in &crossings[h] {
+            if !reached_vertical[v] {
+                reached_vertical[v] = true;
+                if let Some(next) = matched_vertical[v].filter(|&next| !reached_horizontal[next]) {
+                    reached_horizontal[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    let horizontal = horizontal.iter().zip(&reached_horizontal).filter(|&(_, &reached)| reached).map(|(c, _)| *c).collect();
+    let vertical = vertical.iter().zip(&reached_vertical).filter(|&(_, &reached)| !reached).map(|(c, _)| *c).collect();
+    (horizontal, vertical)
+}
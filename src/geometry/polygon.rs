@@ -62,6 +62,11 @@ impl Polygon {
     }
 
     fn update(&mut self, points: &[Point]) {
+        // The bounds of an empty polygon start at its first points, not the origin.
+        if let (true, Some(&first)) = (self.points.len() == points.len(), points.first()) {
+            self.min = first;
+            self.max = first;
+        }
         for p in points {
             self.update_bounds(p);
         }
@@ -136,4 +141,55 @@ impl Polygon {
         crossings % 2 == 1
     }
 
+    /// The runs of points inside the polygon on each row from `min.y` to `max.y`, as half-open `(begin, end)` x ranges.
+    /// <br> Agrees with `contains` point by point, but sweeps the rows with the edges crossing them instead of testing every point.
+    pub fn row_spans(&self) -> Vec<Vec<(i32, i32)>> {
+        let n = self.points.len();
+
+        // Each edge crosses the rows from its lower end up to, but not including, its upper end.
+        let mut edges: Vec<(i32, i32, usize)> = (0..n)
+            .filter_map(|i| {
+                let (p1, p2) = (self.points[i], self.points[(i + 1) % n]);
+                (p1.y != p2.y).then(|| (p1.y.min(p2.y), p1.y.max(p2.y), i))
+            })
+            .collect();
+        edges.sort_unstable();
+
+        let mut pending = edges.into_iter().peekable();
+        let mut active: Vec<(i32, usize)> = Vec::new();
+        let mut crossings: Vec<i32> = Vec::new();
+        let mut rows = Vec::with_capacity(self.size.y as usize + 1);
+
+        for y in self.min.y..=self.max.y {
+            active.retain(|&(upper, _)| upper > y);
+            while let Some((_, upper, i)) = pending.next_if(|&(lower, _, _)| lower <= y) {
+                active.push((upper, i));
+            }
+
+            crossings.clear();
+            crossings.extend(active.iter().map(|&(_, i)| {
+                let (p1, p2) = (&self.points[i], &self.points[(i + 1) % n]);
+                (p2.x - p1.x) * (y - p1.y) / (p2.y - p1.y) + p1.x
+            }));
+            crossings.sort_unstable();
+
+            // Points between the crossings `i - 1` and `i` have `k - i` crossings to their right.
+            let k = crossings.len();
+            let mut spans: Vec<(i32, i32)> = Vec::new();
+            for i in (0..k).filter(|i| (k - i) % 2 == 1) {
+                let begin = if i == 0 { self.min.x } else { crossings[i - 1].max(self.min.x) };
+                let end = crossings[i].min(self.max.x + 1);
+                if begin >= end {
+                    continue;
+                }
+                match spans.last_mut() {
+                    Some(last) if last.1 == begin => last.1 = end,
+                    _ => spans.push((begin, end)),
+                }
+            }
+            rows.push(spans);
+        }
+        rows
+    }
+
 }
\ No newline at end of file
@@ -1,9 +1,38 @@
-
-
-
-
-
-
-
-
-
+#![allow(dead_code)]
+
+//! Generates circular, rectangular, hexagonal and triangular mazes and exports them as Brickadia bricks.
+//!
+//! ```no_run
+//! use brickadia_gen_v4::{MazeTopology, ThetaMaze, save_bricks};
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let maze = ThetaMaze::builder()
+//!     .rings(12)
+//!     .ring_width(100)
+//!     .initial_divisions(4)
+//!     .seed((11, 12, 15, 2))
+//!     .generate()?;
+//!
+//! let build = maze.build(true, true);
+//! build.save_images("maze.png", "maze_bricks.png")?;
+//! save_bricks(build.bricks, "ThetaMaze", None)?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod config;
+pub mod geometry;
+pub mod maze;
+pub mod draw;
+pub mod utils;
+
+pub mod metadata;
+
+pub use brickadia::save::Brick;
+pub use config::{ConfigError, MazeConfig, Topology};
+pub use maze::{
+    search_seeds, Algorithm, Constraint, DeltaMaze, DeltaMazeBuilder, GridMaze, GridMazeBuilder, HexMaze, HexMazeBuilder,
+    Layout, MazeBuild, MazeGraph, MazeStats, MazeTopology, Measure, RowStats, RowsMaze, RowsMazeBuilder, Selection, Slab,
+    Solve, Subdivision, ThetaMaze, ThetaMazeBuilder,
+};
+pub use metadata::save::save_bricks;
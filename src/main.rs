@@ -1,12 +1,472 @@
-#![allow(dead_code)]
+use std::error::Error;
+use std::path::PathBuf;
 
-pub mod geometry;
-pub mod maze;
-pub mod draw;
-pub mod utils;
+use brickadia::save::Brick;
+use clap::{Args, Parser, Subcommand};
+use image::RgbImage;
 
-pub mod metadata;
+use brickadia_gen_v4::draw::colors::{BLACK, WHITE};
+use brickadia_gen_v4::draw::draw::DebugImage;
+use brickadia_gen_v4::geometry::arc::{Arc, Precision, WedgeArc, WedgeStyle};
+use brickadia_gen_v4::geometry::decompose::Partition;
+use brickadia_gen_v4::utils::points::Point;
+use brickadia_gen_v4::{
+    save_bricks, search_seeds, Algorithm, Constraint, MazeBuild, MazeConfig, MazeTopology, Measure,
+    Selection, Slab, Subdivision, Topology,
+};
 
+#[derive(Parser)]
+#[command(version, about = "Generates mazes and shapes as Brickadia saves.")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Generates a circular maze of concentric rings.
+    Theta(ThetaArgs),
+    /// Generates a rectangular maze of square cells.
+    Grid(RowsArgs),
+    /// Generates a maze of hexagonal cells.
+    Hex(RowsArgs),
+    /// Generates a maze of triangular cells.
+    Delta(RowsArgs),
+    /// Builds a single thick arc.
+    Arc(ArcArgs),
+    /// Generates a maze described by a `.toml` or `.json` config file, or a built-in preset.
+    Config(ConfigArgs),
+    /// Lists the seeds whose mazes meet every constraint.
+    /// Starts at --seed, incrementing its last value for each following seed.
+    Search(SearchArgs),
+}
+
+/// Options shared by every subcommand for naming and placing the outputs.
+#[derive(Args)]
+struct OutputArgs {
+    /// Name of the `.brs` save, without the extension.
+    #[arg(short, long, default_value = "Maze")]
+    name: String,
+    /// Directory to write the save to. Defaults to the Brickadia builds folder.
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+    /// Draws the debug images.
+    #[arg(short, long)]
+    draw: bool,
+    /// Path of the debug image of the shape.
+    #[arg(long, default_value = "maze.png")]
+    image: PathBuf,
+    /// Path of the debug image of the bricks.
+    #[arg(long, default_value = "maze_bricks.png")]
+    bricks_image: PathBuf,
+}
+
+/// The parameters of a theta maze.
+#[derive(Args)]
+struct MazeArgs {
+    /// Number of concentric rings.
+    #[arg(short, long, default_value_t = 3)]
+    rings: usize,
+    /// Width of each ring in micro-bricks.
+    #[arg(short = 'w', long, default_value_t = 100)]
+    ring_width: u32,
+    /// Divisions of the innermost ring. Doubles every two rings unless --subdivision says otherwise.
+    #[arg(short, long, default_value_t = 2)]
+    initial_divisions: usize,
+    /// How the outer rings are split into more cells: "doubling" every two rings,
+    /// or "arc_length=1.5" to split cells longer than 1.5 ring widths.
+    /// Checked like the config key, so cells split thinner than their walls are refused.
+    #[arg(long, default_value_t = Subdivision::default())]
+    subdivision: Subdivision,
+    #[command(flatten)]
+    carving: CarvingArgs,
+}
+
+/// How the passages of any maze are carved.
+#[derive(Args)]
+struct CarvingArgs {
+    /// Four comma separated `u32` values. The same seed generates the same maze.
+    #[arg(short, long, value_parser = parse_seed, default_value = "11,12,15,2")]
+    seed: (u32, u32, u32, u32),
+    /// Maze generation algorithm: backtracker, wilson, kruskal, prim or growing_tree.
+    #[arg(short, long, default_value_t = Algorithm::Backtracker)]
+    algorithm: Algorithm,
+    /// How the growing tree picks its next cell, e.g. "75% newest / 25% random".
+    #[arg(long, default_value_t = Selection::default())]
+    selection: Selection,
+    /// Fraction of dead ends, from 0.0 to 1.0, opened into loops.
+    #[arg(short, long, default_value_t = 0.0)]
+    braid: f32,
+}
+
+#[derive(Args)]
+struct ThetaArgs {
+    #[command(flatten)]
+    maze: MazeArgs,
+    /// Draws the solution path on the maze image.
+    #[arg(long)]
+    solve: bool,
+    /// Prints the dead ends, junctions, solution length and other statistics of the maze.
+    #[arg(long)]
+    stats: bool,
+    /// Thickness of the walls in micro-bricks.
+    #[arg(short = 'g', long, default_value_t = 50)]
+    radius_gap: u32,
+    /// Vertical size of the wall bricks.
+    #[arg(long, default_value_t = 100)]
+    height: u32,
+    /// Height of the bottom of the walls above the ground, to place the maze on top of a build.
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    elevation: i32,
+    /// Merges the micro-bricks greedily, or into the fewest rectangles with "minimum".
+    #[arg(long, default_value_t = Partition::Greedy)]
+    partition: Partition,
+    /// Builds a grey floor under the maze.
+    #[arg(long)]
+    floor: bool,
+    /// Builds a grey ceiling on top of the walls.
+    #[arg(long)]
+    ceiling: bool,
+    /// Curved walls of simple steps, or with corner wedges where their steps meet.
+    #[arg(long, default_value_t = WedgeStyle::Simple)]
+    wedges: WedgeStyle,
+    /// How closely the curved walls follow their circles: "tolerance=0.5" or "max_wedge_length=30" micro-bricks.
+    #[arg(short, long, default_value_t = Precision::default())]
+    precision: Precision,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// The parameters of a grid, hex or delta maze.
+#[derive(Args)]
+struct RowsArgs {
+    /// Number of cells across.
+    #[arg(short = 'x', long, default_value_t = 10)]
+    columns: usize,
+    /// Number of cells down.
+    #[arg(short = 'y', long, default_value_t = 10)]
+    rows: usize,
+    /// Micro-bricks between the walls of a grid cell, between the centres of hex cells, or along the sides of delta cells.
+    /// Defaults to 100, or 150 for delta mazes.
+    #[arg(short = 'c', long)]
+    cell_size: Option<u32>,
+    #[command(flatten)]
+    carving: CarvingArgs,
+    /// Draws the solution path on the maze image.
+    #[arg(long)]
+    solve: bool,
+    /// Prints the dead ends, junctions, solution length and other statistics of the maze.
+    #[arg(long)]
+    stats: bool,
+    /// Thickness of the walls in micro-bricks. Defaults to 50 for grid mazes, or 30.
+    #[arg(short = 'g', long)]
+    wall_thickness: Option<u32>,
+    /// Vertical size of the wall bricks.
+    #[arg(long, default_value_t = 100)]
+    height: u32,
+    /// Height of the bottom of the walls above the ground, to place the maze on top of a build.
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    elevation: i32,
+    /// Builds a grey floor under the maze.
+    #[arg(long)]
+    floor: bool,
+    /// Builds a grey ceiling on top of the walls.
+    #[arg(long)]
+    ceiling: bool,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+#[derive(Args)]
+struct ArcArgs {
+    /// Outer radius of the arc in micro-bricks.
+    #[arg(short, long, default_value_t = 650)]
+    radius: u32,
+    /// Thickness of the arc in micro-bricks.
+    #[arg(short = 'g', long, default_value_t = 50)]
+    radius_gap: u32,
+    /// Vertical size of the bricks.
+    #[arg(long, default_value_t = 100)]
+    height: u32,
+    /// Merges the micro-bricks greedily, or into the fewest rectangles with "minimum".
+    #[arg(long, default_value_t = Partition::Greedy)]
+    partition: Partition,
+    /// Height of the bottom of the bricks above the ground.
+    #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+    elevation: i32,
+    /// Start angle in degrees.
+    #[arg(short, long, default_value_t = 0.0)]
+    begin: f32,
+    /// End angle in degrees.
+    #[arg(short, long, default_value_t = 180.0)]
+    end: f32,
+    /// Simple steps, or corner wedges where the steps meet.
+    #[arg(long, default_value_t = WedgeStyle::Simple)]
+    wedges: WedgeStyle,
+    /// How closely the arc follows its circle: "tolerance=0.5" or "max_wedge_length=30" micro-bricks.
+    #[arg(short, long, default_value_t = Precision::default())]
+    precision: Precision,
+    #[command(flatten)]
+    output: OutputArgs,
+}
+
+/// Parses a seed of four comma separated `u32` values.
+fn parse_seed(arg: &str) -> Result<(u32, u32, u32, u32), String> {
+    let values = arg
+        .split(',')
+        .map(|v| v.trim().parse::<u32>().map_err(|e| format!("'{}': {}", v, e)))
+        .collect::<Result<Vec<u32>, String>>()?;
+
+    match values[..] {
+        [a, b, c, d] => Ok((a, b, c, d)),
+        _ => Err(format!("expected 4 values, found {}", values.len())),
+    }
+}
+
+#[derive(Args)]
+struct ConfigArgs {
+    /// Path of the config file.
+    #[arg(required_unless_present = "preset", conflicts_with = "preset")]
+    path: Option<PathBuf>,
+    /// Name of a built-in preset: small, medium or huge.
+    #[arg(short, long)]
+    preset: Option<String>,
+    /// Prints the config as TOML instead of generating the maze.
+    #[arg(long)]
+    print: bool,
+    /// Prints the dead ends, junctions, solution length and other statistics of the maze.
+    #[arg(long)]
+    stats: bool,
+}
+
+#[derive(Args)]
+struct SearchArgs {
+    /// Layout of the mazes: theta, grid, hex or delta.
+    #[arg(short, long, default_value_t = Topology::Theta)]
+    topology: Topology,
+    #[command(flatten)]
+    maze: MazeArgs,
+    /// Number of cells across a grid, hex or delta maze.
+    #[arg(short = 'x', long, default_value_t = 10)]
+    columns: usize,
+    /// Number of cells down a grid, hex or delta maze.
+    #[arg(short = 'y', long, default_value_t = 10)]
+    rows: usize,
+    /// Number of seeds to generate.
+    #[arg(short, long, default_value_t = 1000)]
+    count: u32,
+    /// A constraint such as "solution_ratio >= 0.6" or "dead_ends <= 20". Can be repeated.
+    /// Metrics: dead_ends, junctions, longest_corridor, solution_length, solution_ratio, river_factor, average_branch_depth.
+    #[arg(short = 'W', long = "where")]
+    constraints: Vec<Constraint>,
+    /// Stops listing after this many seeds.
+    #[arg(short, long)]
+    limit: Option<usize>,
+}
+
+impl MazeArgs {
+    /// The config of the theta maze, with every other key at its default.
+    fn config(&self) -> MazeConfig {
+        let carving = &self.carving;
+        MazeConfig {
+            algorithm: carving.algorithm,
+            selection: carving.selection,
+            braid: carving.braid,
+            rings: self.rings,
+            ring_width: self.ring_width,
+            initial_divisions: self.initial_divisions,
+            subdivision: self.subdivision,
+            seed: carving.seed,
+            ..MazeConfig::default()
+        }
+    }
+}
+
+/// Any failure of a command: a rejected config, or a save that couldn't be written.
+type CommandResult = Result<(), Box<dyn Error>>;
+
+impl OutputArgs {
+    fn save(&self, build: MazeBuild) -> CommandResult {
+        if build.deviation > 0.0 {
+            println!("{} bricks, curves within {:.2} micro-bricks of their circles", build.bricks.len(), build.deviation);
+        }
+        build.save_images(&self.image.to_string_lossy(), &self.bricks_image.to_string_lossy())?;
+        save_bricks(build.bricks, &self.name, self.output.as_deref())?;
+        Ok(())
+    }
+}
+
+/// Generates a theta maze through its config, which checks the rings fit their walls.
+fn theta(args: ThetaArgs) -> CommandResult {
+    let config = MazeConfig {
+        radius_gap: args.radius_gap,
+        wall_height: args.height,
+        elevation: args.elevation,
+        wedge_style: args.wedges,
+        precision: args.precision,
+        partition: args.partition,
+        floor: args.floor.then(Slab::default),
+        ceiling: args.ceiling.then(Slab::default),
+        ..args.maze.config()
+    };
+    config.validate()?;
+
+    let maze = config.theta_builder().generate()?;
+
+    if args.maze.carving.braid > 0.0 {
+        println!("Braided {} loops", maze.loops());
+    }
+    if args.stats {
+        print!("{}", maze.stats());
+    }
+
+    args.output.save(maze.build(args.output.draw, args.solve))
+}
+
+/// Generates a grid, hex or delta maze through its config, which checks the sizes.
+fn rows(topology: Topology, args: RowsArgs) -> CommandResult {
+    let (cell_size, wall_thickness) = match topology {
+        Topology::Grid => (100, 50),
+        Topology::Delta => (150, 30),
+        _ => (100, 30),
+    };
+
+    let carving = &args.carving;
+    let config = MazeConfig {
+        topology,
+        algorithm: carving.algorithm,
+        selection: carving.selection,
+        braid: carving.braid,
+        columns: args.columns,
+        rows: args.rows,
+        cell_size: args.cell_size.unwrap_or(cell_size),
+        radius_gap: args.wall_thickness.unwrap_or(wall_thickness),
+        wall_height: args.height,
+        elevation: args.elevation,
+        floor: args.floor.then(Slab::default),
+        ceiling: args.ceiling.then(Slab::default),
+        seed: carving.seed,
+        ..MazeConfig::default()
+    };
+
+    let maze = config.generate()?;
+    if carving.braid > 0.0 {
+        println!("Braided {} loops", maze.loops());
+    }
+    if args.stats {
+        print!("{}", maze.stats());
+    }
+
+    args.output.save(maze.build(args.output.draw, args.solve))
+}
+
+fn arc(args: ArcArgs) -> CommandResult {
+    let padding = 50;
+    let size = args.radius * 2 + padding * 2;
+    let mid = size as i32 / 2;
+
+    let canvas = args.output.draw.then(|| RgbImage::from_pixel(size, size, WHITE));
+    let mut debug = DebugImage::new(canvas, BLACK);
+
+    let mut wedge_arc = WedgeArc {
+        rgb: BLACK,
+        arc: Arc {
+            begin_angle: args.begin.to_radians(),
+            end_angle: args.end.to_radians(),
+            centre: Point::new(mid, mid),
+            is_inner_arc: false,
+            radius: args.radius,
+            precision: args.precision,
+        },
+        radius_gap: args.radius_gap,
+        height: args.height,
+        elevation: args.elevation,
+        style: args.wedges,
+        partition: args.partition,
+    };
+
+    let mut bricks: Vec<Brick> = Vec::new();
+    wedge_arc.compute(&mut debug, &mut bricks);
+
+    args.output.save(MazeBuild {
+        bricks,
+        maze_image: None,
+        bricks_image: debug.into_image(),
+        deviation: wedge_arc.deviation(),
+    })
+}
+
+fn config(args: ConfigArgs) -> CommandResult {
+    let config = match (&args.path, &args.preset) {
+        (Some(path), _) => MazeConfig::from_file(path)?,
+        (None, Some(preset)) => MazeConfig::preset(preset)?,
+        (None, None) => unreachable!("clap requires a path or a preset"),
+    };
+
+    if args.print {
+        print!("{}", config.to_toml());
+        return Ok(());
+    }
+
+    let output = &config.output;
+    let maze = config.generate()?;
+    if config.braid > 0.0 {
+        println!("Braided {} loops", maze.loops());
+    }
+    if args.stats {
+        print!("{}", maze.stats());
+    }
+
+    let build = maze.build(output.draw, output.solve);
+    if build.deviation > 0.0 {
+        println!("{} bricks, curves within {:.2} micro-bricks of their circles", build.bricks.len(), build.deviation);
+    }
+    build.save_images(&output.image.to_string_lossy(), &output.bricks_image.to_string_lossy())?;
+    save_bricks(build.bricks, &output.name, output.directory.as_deref())?;
+    Ok(())
+}
+
+fn search(args: SearchArgs) -> CommandResult {
+    let config = MazeConfig {
+        topology: args.topology,
+        columns: args.columns,
+        rows: args.rows,
+        ..args.maze.config()
+    };
+    config.validate()?;
+
+    // Only the seed changes, so every config passes the validation above.
+    let measure = |seed| MazeConfig { seed, ..config.clone() }.generate().expect("validated config").stats();
+    let found = search_seeds(measure, config.seed, args.count, &args.constraints);
+
+    println!("{} of {} seeds matched", found.len(), args.count);
+    println!("Seed                      Dead ends  Junctions  Solution  River");
+    for ((a, b, c, d), stats) in found.iter().take(args.limit.unwrap_or(usize::MAX)) {
+        println!(
+            "{:<24}  {:>9}  {:>9}  {:>7.0}%  {:>5.2}",
+            format!("{},{},{},{}", a, b, c, d),
+            stats.dead_ends,
+            stats.junctions,
+            stats.solution_ratio * 100.0,
+            stats.river_factor
+        );
+    }
+    Ok(())
+}
 
 fn main() {
+    let result = match Cli::parse().command {
+        Command::Theta(args) => theta(args),
+        Command::Grid(args) => rows(Topology::Grid, args),
+        Command::Hex(args) => rows(Topology::Hex, args),
+        Command::Delta(args) => rows(Topology::Delta, args),
+        Command::Arc(args) => arc(args),
+        Command::Search(args) => search(args),
+        Command::Config(args) => config(args),
+    };
+
+    if let Err(e) = result {
+        eprintln!("Error: {}", e);
+        std::process::exit(1);
+    }
 }
@@ -0,0 +1,77 @@
+use crate::utils::sfc32::{random_range, shuffle};
+use super::generators::Rng;
+use super::graph::MazeGraph;
+
+/// Removes a `fraction` of the dead ends by opening one more of their walls, turning a perfect maze into a braided one.
+/// <br> Walls leading into other dead ends are preferred, since opening them removes two dead ends at once.
+/// <br> Returns the number of walls opened, each of which closes a loop.
+pub fn braid(maze: &mut dyn MazeGraph, fraction: f32, rng: Rng) -> usize {
+
+    if fraction <= 0.0 {
+        return 0;
+    }
+
+    let mut dead_ends: Vec<(usize, usize)> = maze.cells()
+        .into_iter()
+        .filter(|&cell| maze.is_dead_end(cell))
+        .collect();
+
+    shuffle(&mut *rng, &mut dead_ends);
+
+    let count = (dead_ends.len() as f32 * fraction.min(1.0)).round() as usize;
+    let mut loops = 0;
+
+    for &cell in dead_ends.iter().take(count) {
+        // An earlier wall may already have opened this dead end.
+        if !maze.is_dead_end(cell) {
+            continue;
+        }
+
+        let walled: Vec<(usize, usize)> = maze.carvable_neighbours(cell)
+            .into_iter()
+            .filter(|&neighbour| maze.has_wall_between(cell, neighbour))
+            .collect();
+
+        let dead_end_neighbours: Vec<(usize, usize)> = walled
+            .iter()
+            .copied()
+            .filter(|&neighbour| maze.is_dead_end(neighbour))
+            .collect();
+
+        let candidates = if dead_end_neighbours.is_empty() { walled } else { dead_end_neighbours };
+        if candidates.is_empty() {
+            continue;
+        }
+
+        let neighbour = candidates[random_range(&mut *rng, 0.0, candidates.len() as f32)];
+        maze.open_wall_between(cell, neighbour);
+        loops += 1;
+    }
+
+    loops
+}
+
+#[cfg(test)]
+mod braid_maze {
+
+    use super::*;
+    use crate::maze::{MazeTopology, ThetaMaze};
+
+    #[test]
+    fn run() {
+
+        let builder = ThetaMaze::builder().rings(8).initial_divisions(4).seed((3, 1, 4, 1));
+
+        let perfect = builder.clone().generate().unwrap();
+        let braided = builder.clone().braid(1.0).generate().unwrap();
+        let dead_ends = |maze: &ThetaMaze| maze.cells().into_iter().filter(|&cell| maze.is_dead_end(cell)).count();
+
+        assert_eq!(perfect.loops(), 0);
+        assert!(braided.loops() > 0);
+        assert_eq!(dead_ends(&braided), 0);
+        assert!(dead_ends(&perfect) > 0);
+
+        // The same seed braids the same walls.
+        assert_eq!(builder.clone().braid(0.5).generate().unwrap().loops(), builder.braid(0.5).generate().unwrap().loops());
+    }
+}
@@ -0,0 +1,347 @@
+use std::marker::PhantomData;
+
+use image::Rgb;
+use crate::config::ConfigError;
+use crate::geometry::arc::{Precision, WedgeStyle};
+use crate::geometry::decompose::Partition;
+use super::generators::Algorithm;
+use super::generators::growing_tree::Selection;
+use super::delta::DeltaLayout;
+use super::grid::GridLayout;
+use super::hex::HexLayout;
+use super::maze::ThetaMaze;
+use super::rows::{Layout, RowsMaze, RowsSettings};
+use super::slab::Slab;
+use super::subdivision::Subdivision;
+
+/// Configures and generates a `ThetaMaze`.
+/// <br> The same parameters and seed will generate the SAME maze.
+#[derive(Clone, Debug)]
+pub struct ThetaMazeBuilder {
+    ring_width: u32,
+    rings: usize,
+    initial_divisions: usize,
+    subdivision: Subdivision,
+    seed: (u32, u32, u32, u32),
+    radius_gap: u32,
+    wall_height: u32,
+    elevation: i32,
+    wall_colour: Option<Rgb<u8>>,
+    wedge_style: WedgeStyle,
+    precision: Precision,
+    partition: Partition,
+    floor: Option<Slab>,
+    ceiling: Option<Slab>,
+    algorithm: Algorithm,
+    selection: Selection,
+    braid: f32,
+}
+
+impl Default for ThetaMazeBuilder {
+    fn default() -> Self {
+        Self {
+            ring_width: 100,
+            rings: 3,
+            initial_divisions: 2,
+            subdivision: Subdivision::default(),
+            seed: (11, 12, 15, 2),
+            radius_gap: 50,
+            wall_height: 100,
+            elevation: 0,
+            wall_colour: None,
+            wedge_style: WedgeStyle::default(),
+            precision: Precision::default(),
+            partition: Partition::default(),
+            floor: None,
+            ceiling: None,
+            algorithm: Algorithm::default(),
+            selection: Selection::default(),
+            braid: 0.0,
+        }
+    }
+}
+
+impl ThetaMazeBuilder {
+    /// Width of each ring in micro-bricks.
+    pub fn ring_width(mut self, ring_width: u32) -> Self {
+        self.ring_width = ring_width;
+        self
+    }
+
+    /// Number of concentric rings.
+    pub fn rings(mut self, rings: usize) -> Self {
+        self.rings = rings;
+        self
+    }
+
+    /// Divisions of the innermost ring, split further out by the `Subdivision`.
+    pub fn initial_divisions(mut self, initial_divisions: usize) -> Self {
+        self.initial_divisions = initial_divisions;
+        self
+    }
+
+    /// How the rings are divided into more cells further out, doubling every two rings by default.
+    pub fn subdivision(mut self, subdivision: Subdivision) -> Self {
+        self.subdivision = subdivision;
+        self
+    }
+
+    /// The `sfc32` seed used to carve the maze.
+    pub fn seed(mut self, seed: (u32, u32, u32, u32)) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The algorithm used to carve the passages.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// How the growing tree algorithm chooses the next cell to carve from.
+    pub fn selection(mut self, selection: Selection) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// The fraction of dead ends, from `0.0` to `1.0`, opened into loops after carving.
+    pub fn braid(mut self, braid: f32) -> Self {
+        self.braid = braid;
+        self
+    }
+
+    /// Thickness of the walls in micro-bricks.
+    pub fn radius_gap(mut self, radius_gap: u32) -> Self {
+        self.radius_gap = radius_gap;
+        self
+    }
+
+    /// Vertical size of the wall bricks.
+    pub fn wall_height(mut self, wall_height: u32) -> Self {
+        self.wall_height = wall_height;
+        self
+    }
+
+    /// Height of the bottom of the walls above the ground, to place the maze on top of a build.
+    pub fn elevation(mut self, elevation: i32) -> Self {
+        self.elevation = elevation;
+        self
+    }
+
+    /// A single colour for every wall, instead of shading the rings.
+    pub fn wall_colour(mut self, wall_colour: Option<Rgb<u8>>) -> Self {
+        self.wall_colour = wall_colour;
+        self
+    }
+
+    /// Whether the curved walls have corner wedges where their steps meet.
+    pub fn wedge_style(mut self, wedge_style: WedgeStyle) -> Self {
+        self.wedge_style = wedge_style;
+        self
+    }
+
+    /// How closely the curved walls follow their circles.
+    pub fn precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// How the micro-bricks of the walls and slabs are merged into rectangles.
+    pub fn partition(mut self, partition: Partition) -> Self {
+        self.partition = partition;
+        self
+    }
+
+    /// A disc of bricks under the walls.
+    pub fn floor(mut self, floor: Option<Slab>) -> Self {
+        self.floor = floor;
+        self
+    }
+
+    /// A disc of bricks resting on top of the walls.
+    pub fn ceiling(mut self, ceiling: Option<Slab>) -> Self {
+        self.ceiling = ceiling;
+        self
+    }
+
+    /// Creates the maze and carves its passages.
+    /// <br> Fails without a ring around the centre, or without divisions in the innermost ring.
+    pub fn generate(self) -> Result<ThetaMaze, ConfigError> {
+        let invalid = |field, reason: &str| Err(ConfigError::Invalid { field, reason: reason.into() });
+
+        if self.rings < 2 {
+            return invalid("rings", "a theta maze needs a centre and at least one ring around it");
+        }
+        if self.initial_divisions == 0 {
+            return invalid("initial_divisions", "the innermost ring of a theta maze needs at least one division");
+        }
+
+        let mut maze = ThetaMaze::new(self.ring_width, self.rings, self.initial_divisions);
+        maze.radius_gap = self.radius_gap;
+        maze.wall_height = self.wall_height;
+        maze.elevation = self.elevation;
+        maze.wall_colour = self.wall_colour;
+        maze.wedge_style = self.wedge_style;
+        maze.precision = self.precision;
+        maze.partition = self.partition;
+        maze.floor = self.floor;
+        maze.ceiling = self.ceiling;
+        maze.subdivision = self.subdivision;
+        maze.algorithm = self.algorithm;
+        maze.selection = self.selection;
+        maze.braid = self.braid;
+        maze.generate(self.seed);
+        Ok(maze)
+    }
+}
+
+/// Configures and generates a grid, hex or delta maze laid out by `L`.
+/// <br> The same parameters and seed will generate the SAME maze.
+#[derive(Clone, Debug)]
+pub struct RowsMazeBuilder<L: Layout> {
+    settings: RowsSettings,
+    seed: (u32, u32, u32, u32),
+    layout: PhantomData<L>,
+}
+
+/// Configures and generates a `GridMaze`.
+pub type GridMazeBuilder = RowsMazeBuilder<GridLayout>;
+
+/// Configures and generates a `HexMaze`.
+pub type HexMazeBuilder = RowsMazeBuilder<HexLayout>;
+
+/// Configures and generates a `DeltaMaze`.
+pub type DeltaMazeBuilder = RowsMazeBuilder<DeltaLayout>;
+
+impl<L: Layout> Default for RowsMazeBuilder<L> {
+    fn default() -> Self {
+        Self {
+            settings: RowsSettings::new::<L>(10, 10, L::CELL_SIZE),
+            seed: (11, 12, 15, 2),
+            layout: PhantomData,
+        }
+    }
+}
+
+impl<L: Layout> RowsMazeBuilder<L> {
+    /// Number of cells across.
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.settings.columns = columns;
+        self
+    }
+
+    /// Number of cells down.
+    pub fn rows(mut self, rows: usize) -> Self {
+        self.settings.rows = rows;
+        self
+    }
+
+    /// Size of the cells in micro-bricks, as the layout measures them. Must leave room inside the walls.
+    pub fn cell_size(mut self, cell_size: u32) -> Self {
+        self.settings.cell_size = cell_size;
+        self
+    }
+
+    /// The `sfc32` seed used to carve the maze.
+    pub fn seed(mut self, seed: (u32, u32, u32, u32)) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The algorithm used to carve the passages.
+    pub fn algorithm(mut self, algorithm: Algorithm) -> Self {
+        self.settings.algorithm = algorithm;
+        self
+    }
+
+    /// How the growing tree algorithm chooses the next cell to carve from.
+    pub fn selection(mut self, selection: Selection) -> Self {
+        self.settings.selection = selection;
+        self
+    }
+
+    /// The fraction of dead ends, from `0.0` to `1.0`, opened into loops after carving.
+    pub fn braid(mut self, braid: f32) -> Self {
+        self.settings.braid = braid;
+        self
+    }
+
+    /// Thickness of the walls in micro-bricks.
+    pub fn wall_thickness(mut self, wall_thickness: u32) -> Self {
+        self.settings.wall_thickness = wall_thickness;
+        self
+    }
+
+    /// Vertical size of the wall bricks.
+    pub fn wall_height(mut self, wall_height: u32) -> Self {
+        self.settings.wall_height = wall_height;
+        self
+    }
+
+    /// Height of the bottom of the walls above the ground, to place the maze on top of a build.
+    pub fn elevation(mut self, elevation: i32) -> Self {
+        self.settings.elevation = elevation;
+        self
+    }
+
+    /// A single colour for every wall, black by default.
+    pub fn wall_colour(mut self, wall_colour: Option<Rgb<u8>>) -> Self {
+        self.settings.wall_colour = wall_colour;
+        self
+    }
+
+    /// A layer of bricks under the walls, out to the outer edge of the maze.
+    pub fn floor(mut self, floor: Option<Slab>) -> Self {
+        self.settings.floor = floor;
+        self
+    }
+
+    /// A layer of bricks resting on top of the walls.
+    pub fn ceiling(mut self, ceiling: Option<Slab>) -> Self {
+        self.settings.ceiling = ceiling;
+        self
+    }
+
+    /// Creates the maze and carves its passages.
+    /// <br> Fails without any cells, or with walls leaving no room inside the cells.
+    pub fn generate(self) -> Result<RowsMaze<L>, ConfigError> {
+        let invalid = |field, reason: String| Err(ConfigError::Invalid { field, reason });
+
+        let RowsSettings { columns, rows, cell_size, wall_thickness, .. } = self.settings;
+        if columns == 0 || rows == 0 {
+            return invalid("columns", format!("a {} maze needs at least one cell", L::NAME));
+        }
+        if !L::leaves_room(cell_size, wall_thickness) {
+            return invalid("wall_thickness", format!("the walls of a {} maze must leave room inside its cells", L::NAME));
+        }
+
+        let mut maze = RowsMaze::with_settings(self.settings);
+        maze.generate(self.seed);
+        Ok(maze)
+    }
+}
+
+#[cfg(test)]
+mod reject_parameters {
+
+    use super::*;
+    use crate::maze::{DeltaMaze, GridMaze};
+
+    /// The field a builder rejected, if any.
+    fn field<T>(result: Result<T, ConfigError>) -> &'static str {
+        match result {
+            Err(ConfigError::Invalid { field, .. }) => field,
+            _ => "",
+        }
+    }
+
+    #[test]
+    fn run() {
+
+        assert_eq!(field(ThetaMaze::builder().rings(1).generate()), "rings");
+        assert_eq!(field(ThetaMaze::builder().initial_divisions(0).generate()), "initial_divisions");
+        assert_eq!(field(GridMaze::builder().columns(0).generate()), "columns");
+        assert_eq!(field(DeltaMaze::builder().cell_size(50).wall_thickness(30).generate()), "wall_thickness");
+        assert!(GridMaze::builder().columns(3).rows(2).generate().is_ok());
+    }
+}
@@ -0,0 +1,196 @@
+use brickadia::save::Brick;
+
+use crate::draw::colors::BLACK;
+use crate::draw::draw::DebugImage;
+use crate::geometry::polygon::Polygon;
+use super::graph::MazeGraph;
+use super::rows::{Layout, RowsMaze, RowsSettings};
+use super::slab::Slab;
+use super::topology::MazeTopology;
+use super::walls::{rim_outline, Vertex, WallStyle};
+
+/// Space around the walls on the debug images, in micro-bricks.
+const PADDING: u32 = 50;
+
+// The sloping walls either side of a cell, and the flat wall across its base or top.
+const LEFT: usize = 0;
+const RIGHT: usize = 1;
+const ACROSS: usize = 2;
+
+/// Equilateral triangles, alternately pointing up and down along each row, the top left one pointing up.
+#[derive(Clone, Copy, Debug)]
+pub struct DeltaLayout;
+
+impl DeltaLayout {
+    /// Whether the cell points up, sharing its base with the cell below.
+    fn points_up((row, column): (usize, usize)) -> bool {
+        (row + column) % 2 == 0
+    }
+}
+
+impl Layout for DeltaLayout {
+    type Walls = [bool; 3];
+
+    const NAME: &'static str = "delta";
+    const WALLS: [bool; 3] = [true; 3];
+    /// The length of the sides of the cells.
+    const CELL_SIZE: u32 = 150;
+    const WALL_THICKNESS: u32 = 30;
+
+    fn step(cell: (usize, usize), direction: usize) -> (isize, isize) {
+        match direction {
+            LEFT => (0, -1),
+            RIGHT => (0, 1),
+            _ if Self::points_up(cell) => (1, 0),
+            _ => (-1, 0),
+        }
+    }
+
+    /// The sloping walls face the other way from the neighbour, the flat wall is shared across.
+    fn opposite(direction: usize) -> usize {
+        [RIGHT, LEFT, ACROSS][direction]
+    }
+
+    /// The walls take their thickness from each of the three sides, so must be thinner than the sides over `√3`.
+    fn leaves_room(cell_size: u32, wall_thickness: u32) -> bool {
+        wall_thickness as f32 * 3f32.sqrt() < cell_size as f32
+    }
+}
+
+/// A delta maze of equilateral triangles, alternately pointing up and down along each row.
+/// <br> The top left cell points up. The maze is entered through the left wall of the top left cell
+/// <br> and left through the right wall of the bottom right cell. Cells are `(row, column)` pairs.
+pub type DeltaMaze = RowsMaze<DeltaLayout>;
+
+impl DeltaMaze {
+    /// The corners at either end of the wall in `direction`, in half sides across and rows down.
+    fn corners(&self, cell: (usize, usize), direction: usize) -> (Vertex, Vertex) {
+        let (row, column) = (cell.0 as i32, cell.1 as i32);
+        if DeltaLayout::points_up(cell) {
+            let (apex, left, right) = ((column + 1, row), (column, row + 1), (column + 2, row + 1));
+            [(apex, left), (apex, right), (left, right)][direction]
+        } else {
+            let (left, right, bottom) = ((column, row), (column + 2, row), (column + 1, row + 1));
+            [(left, bottom), (right, bottom), (left, right)][direction]
+        }
+    }
+
+    /// Whether the outer wall of the cell is open in `direction`.
+    fn is_opening(&self, cell: (usize, usize), direction: usize) -> bool {
+        (cell == self.entrance() && direction == LEFT) || (cell == self.exit() && direction == RIGHT)
+    }
+
+    /// Every wall standing, once each, as the corners at either end.
+    fn walls(&self) -> Vec<(Vertex, Vertex)> {
+        let mut walls = Vec::new();
+        for cell in self.cells() {
+            for direction in 0..3 {
+                let standing = match self.neighbour(cell, direction) {
+                    Some(neighbour) => neighbour > cell && self.has_wall(cell, direction),
+                    None => !self.is_opening(cell, direction),
+                };
+                if standing {
+                    walls.push(self.corners(cell, direction));
+                }
+            }
+        }
+        walls
+    }
+
+    /// The position of a point on the lattice of corners in micro-bricks on the debug images.
+    /// <br> The walls are a whole thickness from the edge, to leave room for the points of mitred corners.
+    fn position(&self, (x, y): (f32, f32)) -> (f32, f32) {
+        let RowsSettings { cell_size, wall_thickness, .. } = self.settings;
+        let origin = (PADDING + wall_thickness) as f32;
+        let height = cell_size as f32 * 3f32.sqrt() / 2.0;
+        (origin + x * cell_size as f32 / 2.0, origin + y * height)
+    }
+
+    fn vertex(&self, (x, y): Vertex) -> (f32, f32) {
+        self.position((x as f32, y as f32))
+    }
+}
+
+impl MazeTopology for DeltaMaze {
+    fn loops(&self) -> usize {
+        self.loops
+    }
+
+    fn canvas_size(&self) -> (u32, u32) {
+        let RowsSettings { columns, rows, wall_thickness, .. } = self.settings;
+        let (x, y) = self.vertex((columns as i32 + 1, rows as i32));
+        let margin = (PADDING + wall_thickness) as f32;
+        ((x + margin).ceil() as u32, (y + margin).ceil() as u32)
+    }
+
+    /// A third of the way up from the base of the cell.
+    fn cell_centre(&self, cell: (usize, usize)) -> (f32, f32) {
+        let third = if DeltaLayout::points_up(cell) { 2.0 / 3.0 } else { 1.0 / 3.0 };
+        self.position((cell.1 as f32 + 1.0, cell.0 as f32 + third))
+    }
+
+    /// The flat walls are straight micro-bricks, the sloping walls are stepped with wedges.
+    fn build_bricks(&self, maze_debug: &mut DebugImage, bricks_debug: &mut DebugImage, bricks: &mut Vec<Brick>) -> f32 {
+        let RowsSettings { wall_thickness, wall_height, elevation, wall_colour, .. } = self.settings;
+        let style = WallStyle {
+            rgb: wall_colour.unwrap_or(BLACK),
+            thickness: wall_thickness,
+            height: wall_height,
+            elevation,
+        };
+        style.build(&self.walls(), |vertex| self.vertex(vertex), maze_debug, bricks_debug, bricks);
+        0.0
+    }
+
+    fn outline(&self) -> Polygon {
+        let rim: Vec<(Vertex, Vertex)> = self.rim().into_iter().map(|(cell, direction)| self.corners(cell, direction)).collect();
+        rim_outline(&rim, |vertex| self.vertex(vertex), self.settings.wall_thickness)
+    }
+
+    fn slabs(&self) -> Vec<(Slab, i32)> {
+        self.settings.slabs()
+    }
+}
+
+#[cfg(test)]
+mod delta_maze {
+
+    use strum::IntoEnumIterator;
+    use super::*;
+    use crate::maze::generators::Algorithm;
+    use crate::maze::graph::checks::{assert_perfect, assert_walls_drawn_once};
+    use crate::maze::Solve;
+    use crate::metadata::assets::BrickAssets;
+
+    #[test]
+    fn run() {
+
+        for algorithm in Algorithm::iter() {
+            let maze = DeltaMaze::builder().columns(11).rows(5).algorithm(algorithm).seed((5, 4, 3, 2)).generate().unwrap();
+            assert_eq!(maze.cells().len(), 55);
+            assert_perfect(&maze, algorithm);
+            assert_eq!(maze.solve().unwrap().last(), Some(&maze.exit()));
+        }
+
+        let maze = DeltaMaze::builder().columns(6).rows(4).generate().unwrap();
+
+        // Neighbours share the same wall from both sides.
+        assert_eq!(maze.neighbours((1, 2)).len(), 3);
+        for cell in maze.cells() {
+            for neighbour in maze.neighbours(cell) {
+                assert!(maze.neighbours(neighbour).contains(&cell));
+                let direction = maze.direction(cell, neighbour);
+                let (a, b) = maze.corners(cell, direction);
+                let (c, d) = maze.corners(neighbour, DeltaLayout::opposite(direction));
+                assert!((a, b) == (c, d) || (a, b) == (d, c), "{:?} and {:?} disagree on their wall", cell, neighbour);
+            }
+        }
+
+        assert_walls_drawn_once(&maze, 3, maze.walls().len());
+
+        // The sloping walls are stepped with wedges.
+        let build = maze.build(false, false);
+        let wedges = build.bricks.iter().filter(|b| b.asset_name_index == BrickAssets::MicroWedge.index() as u32).count();
+        assert!(wedges > 0 && wedges < build.bricks.len());
+    }
+}
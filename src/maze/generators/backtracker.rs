@@ -0,0 +1,40 @@
+use crate::maze::graph::MazeGraph;
+use crate::utils::sfc32::random_range;
+use super::{Generator, Rng};
+
+/// Walks to random unvisited neighbours, backtracking along its path at dead ends.
+pub struct Backtracker;
+
+impl Generator for Backtracker {
+    fn carve(&self, maze: &mut dyn MazeGraph, entrance: (usize, usize), rng: Rng) {
+
+        let mut current = entrance;
+
+        let mut backtrack_path = vec![];
+        let mut goal_entry = maze.borders_goal(entrance).then_some(entrance);
+
+        loop {
+            let candidates = maze.unvisited_neighbours(current);
+            let candidates_len = candidates.len();
+            if !candidates.is_empty() {
+                backtrack_path.push(current);
+                let next = candidates[random_range(&mut *rng, 0.0, candidates_len as f32)];
+                maze.open_wall_between(current, next);
+                current = next;
+                maze.visit(next);
+
+                if maze.borders_goal(next) {
+                    goal_entry = Some(next);
+                }
+            } else if let Some(cell) = backtrack_path.pop() {
+                current = cell;
+            } else {
+                break;
+            }
+        }
+        // Create an entry to the goal on the backtrack path.
+        if let Some(cell) = goal_entry {
+            maze.open_goal(cell);
+        }
+    }
+}
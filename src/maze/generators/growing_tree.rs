@@ -0,0 +1,149 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::maze::graph::MazeGraph;
+use crate::utils::sfc32::random_range;
+use super::{Generator, Rng};
+
+/// Which active cell the growing tree carves from next, as percentages summing to 100.
+/// <br> Parsed from `newest`, `random`, `oldest` or a mix such as `75% newest / 25% random`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Selection {
+    pub newest: u8,
+    pub random: u8,
+    pub oldest: u8,
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Self { newest: 50, random: 50, oldest: 0 }
+    }
+}
+
+impl FromStr for Selection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut selection = Self { newest: 0, random: 0, oldest: 0 };
+
+        for part in s.split('/') {
+            let part = part.trim();
+            let (weight, name) = match part.split_once('%') {
+                Some((weight, name)) => (
+                    weight.trim().parse::<u8>().map_err(|e| format!("'{}': {}", part, e))?,
+                    name.trim(),
+                ),
+                None => (100, part),
+            };
+            let slot = match name.to_ascii_lowercase().as_str() {
+                "newest" => &mut selection.newest,
+                "random" => &mut selection.random,
+                "oldest" => &mut selection.oldest,
+                _ => return Err(format!("unknown selection '{}', expected newest, random or oldest", name)),
+            };
+            *slot = slot.saturating_add(weight);
+        }
+
+        let total = selection.newest as u32 + selection.random as u32 + selection.oldest as u32;
+        if total != 100 {
+            return Err(format!("'{}' adds up to {}%, not 100%", s, total));
+        }
+        Ok(selection)
+    }
+}
+
+impl TryFrom<String> for Selection {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for Selection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [(self.newest, "newest"), (self.random, "random"), (self.oldest, "oldest")]
+            .into_iter()
+            .filter(|&(weight, _)| weight > 0)
+            .map(|(weight, name)| if weight == 100 { name.to_string() } else { format!("{}% {}", weight, name) })
+            .collect();
+        write!(f, "{}", parts.join(" / "))
+    }
+}
+
+impl From<Selection> for String {
+    fn from(selection: Selection) -> Self {
+        selection.to_string()
+    }
+}
+
+impl Selection {
+    /// Picks the index of the next active cell.
+    /// <br> The weights are added as `u32`, so hand-built selections over 100% can't overflow.
+    fn select(&self, rng: Rng, active: usize) -> usize {
+        let roll = (rng() * 100.0) as u32;
+        let (newest, random) = (self.newest as u32, self.random as u32);
+        if roll < newest {
+            active - 1
+        } else if roll < newest + random {
+            random_range(&mut *rng, 0.0, active as f32)
+        } else {
+            0
+        }
+    }
+}
+
+/// Carves from a list of active cells, retiring each once it has no unvisited neighbours.
+/// <br> Always choosing the newest cell behaves like the backtracker, always choosing a random one like Prim's.
+pub struct GrowingTree {
+    pub selection: Selection,
+}
+
+impl Generator for GrowingTree {
+    fn carve(&self, maze: &mut dyn MazeGraph, entrance: (usize, usize), rng: Rng) {
+
+        let mut active = vec![entrance];
+
+        while !active.is_empty() {
+            let index = self.selection.select(&mut *rng, active.len());
+            let cell = active[index];
+
+            let candidates = maze.unvisited_neighbours(cell);
+            if candidates.is_empty() {
+                active.remove(index);
+                continue;
+            }
+
+            let next = candidates[random_range(&mut *rng, 0.0, candidates.len() as f32)];
+            maze.open_wall_between(cell, next);
+            maze.visit(next);
+            active.push(next);
+        }
+    }
+}
+
+#[cfg(test)]
+mod parse_selection {
+
+    use super::*;
+
+    #[test]
+    fn run() {
+
+        let mix: Selection = "75% newest / 25% random".parse().unwrap();
+        assert_eq!(mix, Selection { newest: 75, random: 25, oldest: 0 });
+        assert_eq!(mix.to_string().parse::<Selection>().unwrap(), mix);
+
+        assert_eq!("Oldest".parse::<Selection>().unwrap(), Selection { newest: 0, random: 0, oldest: 100 });
+        assert!("50% newest / 25% random".parse::<Selection>().is_err());
+        assert!("100% latest".parse::<Selection>().is_err());
+
+        // Weights built by hand past 100% still pick a cell.
+        let heavy = Selection { newest: 50, random: 250, oldest: 0 };
+        let mut roll = || 0.99;
+        assert!(heavy.select(&mut roll, 4) < 4);
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+use crate::maze::graph::MazeGraph;
+use crate::utils::sfc32::shuffle;
+use super::{Generator, Rng};
+
+/// Randomized Kruskal's algorithm. Opens the walls in a random order,
+/// skipping any wall whose cells are already connected.
+pub struct Kruskal;
+
+/// Disjoint sets of cells, indexed by their position in `MazeGraph::cells`.
+struct UnionFind {
+    parents: Vec<usize>,
+    ranks: Vec<u8>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self { parents: (0..len).collect(), ranks: vec![0; len] }
+    }
+
+    fn find(&mut self, mut set: usize) -> usize {
+        while self.parents[set] != set {
+            // Path halving
+            self.parents[set] = self.parents[self.parents[set]];
+            set = self.parents[set];
+        }
+        set
+    }
+
+    /// Merges the sets of `a` and `b`, returning `false` if they were already the same set.
+    fn union(&mut self, a: usize, b: usize) -> bool {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        match self.ranks[a].cmp(&self.ranks[b]) {
+            std::cmp::Ordering::Less => self.parents[a] = b,
+            std::cmp::Ordering::Greater => self.parents[b] = a,
+            std::cmp::Ordering::Equal => {
+                self.parents[b] = a;
+                self.ranks[a] += 1;
+            }
+        }
+        true
+    }
+}
+
+impl Generator for Kruskal {
+    fn carve(&self, maze: &mut dyn MazeGraph, _entrance: (usize, usize), rng: Rng) {
+
+        let cells = maze.cells();
+
+        // The position of each cell, so the cells can be flattened.
+        let indices: HashMap<(usize, usize), usize> = cells.iter().enumerate().map(|(index, &cell)| (cell, index)).collect();
+        let index = |cell: (usize, usize)| indices[&cell];
+
+        // Every wall between two cells, once.
+        let mut walls: Vec<((usize, usize), (usize, usize))> = cells
+            .iter()
+            .flat_map(|&cell| {
+                maze.carvable_neighbours(cell)
+                    .into_iter()
+                    .filter(move |&neighbour| index(neighbour) > index(cell))
+                    .map(move |neighbour| (cell, neighbour))
+            })
+            .collect();
+        walls.dedup();
+
+        shuffle(&mut *rng, &mut walls);
+
+        let mut sets = UnionFind::new(cells.len());
+        for (a, b) in walls {
+            if sets.union(index(a), index(b)) {
+                maze.open_wall_between(a, b);
+            }
+        }
+
+        for cell in cells {
+            maze.visit(cell);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter, EnumString};
+
+use super::graph::MazeGraph;
+use growing_tree::{GrowingTree, Selection};
+
+pub mod backtracker;
+pub mod growing_tree;
+pub mod kruskal;
+pub mod prim;
+pub mod wilson;
+
+/// A seeded `sfc32` closure, returning numbers in the range `[0, 1)`.
+pub type Rng<'a> = &'a mut dyn FnMut() -> f64;
+
+/// Carves passages through the cells of a `MazeGraph`.
+/// <br> Cells that aren't carvable, like the centre of a theta maze, are visited before carving starts.
+pub trait Generator {
+    /// Visits every cell, opening walls from the visited `entrance` until the maze is connected.
+    fn carve(&self, maze: &mut dyn MazeGraph, entrance: (usize, usize), rng: Rng);
+}
+
+/// The maze generation algorithms, selectable by name.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Display, EnumIter, EnumString, Serialize, Deserialize)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+#[serde(rename_all = "snake_case")]
+pub enum Algorithm {
+    /// Recursive backtracker. Long winding corridors with few branches.
+    #[default]
+    Backtracker,
+    /// Loop-erased random walks. Every possible maze is equally likely.
+    Wilson,
+    /// Joins random walls between unconnected regions. Many short dead ends.
+    Kruskal,
+    /// Grows outwards from random frontier cells. Many short dead ends radiating from the entrance.
+    Prim,
+    /// Carves from a list of active cells, chosen by a `Selection` policy.
+    GrowingTree,
+}
+
+impl Algorithm {
+    /// The generator of this algorithm. Only the growing tree uses the `selection` policy.
+    pub fn generator(self, selection: Selection) -> Box<dyn Generator> {
+        match self {
+            Self::Backtracker => Box::new(backtracker::Backtracker),
+            Self::Wilson => Box::new(wilson::Wilson),
+            Self::Kruskal => Box::new(kruskal::Kruskal),
+            Self::Prim => Box::new(prim::Prim),
+            Self::GrowingTree => Box::new(GrowingTree { selection }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod perfect_mazes {
+
+    use strum::IntoEnumIterator;
+    use crate::maze::ThetaMaze;
+    use crate::maze::graph::checks::assert_perfect;
+    use super::*;
+
+    #[test]
+    fn run() {
+
+        for algorithm in Algorithm::iter() {
+            let maze = ThetaMaze::builder()
+                .rings(6)
+                .initial_divisions(3)
+                .algorithm(algorithm)
+                .seed((1, 2, 3, 4))
+                .generate()
+                .unwrap();
+
+            assert_perfect(&maze, algorithm);
+        }
+    }
+}
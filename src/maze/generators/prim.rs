@@ -0,0 +1,41 @@
+use std::collections::HashSet;
+
+use crate::maze::graph::MazeGraph;
+use crate::utils::sfc32::random_range;
+use super::{Generator, Rng};
+
+/// Randomized Prim's algorithm. Joins a random frontier cell to a random visited neighbour,
+/// then adds its unvisited neighbours to the frontier.
+pub struct Prim;
+
+impl Generator for Prim {
+    fn carve(&self, maze: &mut dyn MazeGraph, entrance: (usize, usize), rng: Rng) {
+
+        let mut frontier: Vec<(usize, usize)> = Vec::new();
+        let mut in_frontier: HashSet<(usize, usize)> = HashSet::new();
+
+        let mut expand = |maze: &dyn MazeGraph, cell: (usize, usize), frontier: &mut Vec<(usize, usize)>| {
+            for neighbour in maze.carvable_neighbours(cell) {
+                if !maze.is_visited(neighbour) && in_frontier.insert(neighbour) {
+                    frontier.push(neighbour);
+                }
+            }
+        };
+
+        expand(maze, entrance, &mut frontier);
+
+        while !frontier.is_empty() {
+            let cell = frontier.swap_remove(random_range(&mut *rng, 0.0, frontier.len() as f32));
+
+            let visited: Vec<(usize, usize)> = maze.carvable_neighbours(cell)
+                .into_iter()
+                .filter(|&neighbour| maze.is_visited(neighbour))
+                .collect();
+            let joined = visited[random_range(&mut *rng, 0.0, visited.len() as f32)];
+
+            maze.open_wall_between(cell, joined);
+            maze.visit(cell);
+            expand(maze, cell, &mut frontier);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use crate::maze::graph::MazeGraph;
+use crate::utils::sfc32::random_range;
+use super::{Generator, Rng};
+
+/// Wilson's algorithm. Random walks from each unvisited cell until they reach the visited maze,
+/// erasing any loops, and carves the remaining walk.
+/// <br> Produces a uniform spanning tree, so it has no bias towards long corridors or short dead ends.
+pub struct Wilson;
+
+impl Generator for Wilson {
+    fn carve(&self, maze: &mut dyn MazeGraph, _entrance: (usize, usize), rng: Rng) {
+
+        for start in maze.cells() {
+            if maze.is_visited(start) {
+                continue;
+            }
+
+            // The index of every cell on the walk, so loops can be erased as soon as they're closed.
+            let mut walk = vec![start];
+            let mut on_walk: HashMap<(usize, usize), usize> = HashMap::from([(start, 0)]);
+
+            let mut current = start;
+            while !maze.is_visited(current) {
+                let neighbours = maze.carvable_neighbours(current);
+                let next = neighbours[random_range(&mut *rng, 0.0, neighbours.len() as f32)];
+
+                if let Some(&index) = on_walk.get(&next) {
+                    for erased in walk.drain(index + 1..) {
+                        on_walk.remove(&erased);
+                    }
+                } else {
+                    on_walk.insert(next, walk.len());
+                    walk.push(next);
+                }
+                current = next;
+            }
+
+            for pair in walk.windows(2) {
+                maze.visit(pair[0]);
+                maze.open_wall_between(pair[0], pair[1]);
+            }
+        }
+    }
+}
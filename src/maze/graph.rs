@@ -0,0 +1,127 @@
+/// The cells of a maze and the walls between them, carved by the generators and walked by the solver.
+/// <br> Cells are `(row, index)` pairs, such as `(ring, division)` in a theta maze.
+pub trait MazeGraph {
+    /// The number of rows of cells.
+    fn rows(&self) -> usize;
+
+    /// The number of cells in a row.
+    fn row_len(&self, row: usize) -> usize;
+
+    /// The cells sharing a wall with `cell`.
+    fn neighbours(&self, cell: (usize, usize)) -> Vec<(usize, usize)>;
+
+    fn is_visited(&self, cell: (usize, usize)) -> bool;
+
+    fn visit(&mut self, cell: (usize, usize));
+
+    /// Whether a wall stands between two neighbouring cells.
+    fn has_wall_between(&self, a: (usize, usize), b: (usize, usize)) -> bool;
+
+    fn open_wall_between(&mut self, a: (usize, usize), b: (usize, usize));
+
+    /// The cell with the opening in the outer wall.
+    fn entrance(&self) -> (usize, usize);
+
+    /// The cells a solution can end in.
+    fn goals(&self) -> Vec<(usize, usize)>;
+
+    /// Whether the generators carve through the cell. Open rooms, like the centre of a theta maze, are left out.
+    fn is_carvable(&self, _cell: (usize, usize)) -> bool {
+        true
+    }
+
+    /// Whether `open_goal` can join the cell to a goal.
+    /// <br> Mazes whose goal is open from the start leave this and `open_goal` as they are.
+    fn borders_goal(&self, _cell: (usize, usize)) -> bool {
+        false
+    }
+
+    /// Opens the wall between the cell and the goal it borders.
+    fn open_goal(&mut self, _cell: (usize, usize)) {}
+
+    /// The number of openings in the outer wall of the cell.
+    fn openings(&self, cell: (usize, usize)) -> usize {
+        (cell == self.entrance()) as usize
+    }
+
+    /// Every cell the generators carve through.
+    fn cells(&self) -> Vec<(usize, usize)> {
+        (0..self.rows())
+            .flat_map(|row| (0..self.row_len(row)).map(move |index| (row, index)))
+            .filter(|&cell| self.is_carvable(cell))
+            .collect()
+    }
+
+    fn carvable_neighbours(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        self.neighbours(cell)
+            .into_iter()
+            .filter(|&neighbour| self.is_carvable(neighbour))
+            .collect()
+    }
+
+    fn unvisited_neighbours(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        self.neighbours(cell)
+            .into_iter()
+            .filter(|&neighbour| !self.is_visited(neighbour))
+            .collect()
+    }
+
+    /// The neighbours which can be walked to from `cell`.
+    fn passages(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        let mut passages: Vec<(usize, usize)> = self.neighbours(cell)
+            .into_iter()
+            .filter(|&neighbour| !self.has_wall_between(cell, neighbour))
+            .collect();
+        // The same neighbour can share two walls, like the cells in a ring of two divisions.
+        passages.dedup();
+        passages
+    }
+
+    /// The number of ways in or out of a cell, counting the openings in the outer wall.
+    fn exits(&self, cell: (usize, usize)) -> usize {
+        self.passages(cell).len() + self.openings(cell)
+    }
+
+    /// Whether a carvable cell has a single way in or out.
+    fn is_dead_end(&self, cell: (usize, usize)) -> bool {
+        self.is_carvable(cell) && self.exits(cell) == 1
+    }
+}
+
+/// Checks shared by the tests of every layout.
+#[cfg(test)]
+pub(crate) mod checks {
+
+    use std::fmt::Display;
+    use super::MazeGraph;
+    use crate::maze::Solve;
+
+    /// A perfect maze is a spanning tree: every cell is visited and there's one passage less than cells.
+    /// <br> Its solution runs from the entrance to a goal.
+    pub(crate) fn assert_perfect(maze: &impl MazeGraph, algorithm: impl Display) {
+        let cells = maze.cells();
+        let passages: usize = cells
+            .iter()
+            .map(|&cell| maze.carvable_neighbours(cell)
+                .into_iter()
+                .filter(|&neighbour| !maze.has_wall_between(cell, neighbour))
+                .count())
+            .sum();
+        assert!(cells.iter().all(|&cell| maze.is_visited(cell)), "{} left cells unvisited", algorithm);
+        assert_eq!(passages / 2, cells.len() - 1, "{} didn't carve a spanning tree", algorithm);
+
+        let solution = maze.solve().unwrap();
+        assert_eq!(solution.first(), Some(&maze.entrance()));
+        assert!(maze.goals().contains(solution.last().unwrap()), "{} didn't reach a goal", algorithm);
+    }
+
+    /// Every wall is drawn once: the walls left inside after carving, and the outer walls but the openings.
+    /// <br> `walls` is the number drawn, `sides` the walls of each cell.
+    pub(crate) fn assert_walls_drawn_once(maze: &impl MazeGraph, sides: usize, walls: usize) {
+        let cells = maze.cells();
+        let shared: usize = cells.iter().map(|&cell| maze.neighbours(cell).len()).sum::<usize>() / 2;
+        let outer = sides * cells.len() - 2 * shared;
+        let openings: usize = cells.iter().map(|&cell| maze.openings(cell)).sum();
+        assert_eq!(walls, (shared - (cells.len() - 1)) + (outer - openings));
+    }
+}
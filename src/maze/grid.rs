@@ -0,0 +1,240 @@
+use brickadia::save::Brick;
+
+use crate::draw::colors::BLACK;
+use crate::draw::draw::DebugImage;
+use crate::geometry::arc::rectangle_bricks;
+use crate::geometry::decompose::render_as_squares;
+use crate::geometry::polygon::Polygon;
+use super::graph::MazeGraph;
+use super::rows::{Layout, RowsMaze, RowsSettings};
+use super::slab::Slab;
+use super::topology::MazeTopology;
+
+/// Space around the walls on the debug images, in micro-bricks.
+const PADDING: u32 = 50;
+
+// The walls of a cell, in the order of its neighbours.
+const LEFT: usize = 0;
+const RIGHT: usize = 1;
+const ABOVE: usize = 2;
+const BELOW: usize = 3;
+
+/// Square cells in rows and columns, each sharing a wall with up to four neighbours.
+#[derive(Clone, Copy, Debug)]
+pub struct GridLayout;
+
+impl Layout for GridLayout {
+    type Walls = [bool; 4];
+
+    const NAME: &'static str = "grid";
+    const WALLS: [bool; 4] = [true; 4];
+    /// The distance between the walls on either side of a cell.
+    const CELL_SIZE: u32 = 100;
+    const WALL_THICKNESS: u32 = 50;
+
+    fn step(_cell: (usize, usize), direction: usize) -> (isize, isize) {
+        [(0, -1), (0, 1), (-1, 0), (1, 0)][direction]
+    }
+
+    fn opposite(direction: usize) -> usize {
+        [RIGHT, LEFT, BELOW, ABOVE][direction]
+    }
+}
+
+/// A rectangular maze of square cells, entered through the top wall of the top left cell
+/// and left through the bottom wall of the bottom right cell.
+/// <br> Cells are `(row, column)` pairs.
+pub type GridMaze = RowsMaze<GridLayout>;
+
+impl GridMaze {
+    /// Whether a wall runs along the horizontal grid line `line`, above the cell in `column`.
+    /// <br> The outer walls are only open at the entrance and the exit.
+    fn horizontal_wall(&self, line: usize, column: usize) -> bool {
+        if line == 0 {
+            column != self.entrance().1
+        } else if line == self.settings.rows {
+            column != self.exit().1
+        } else {
+            self.has_wall((line - 1, column), BELOW)
+        }
+    }
+
+    /// Whether a wall runs along the vertical grid line `line`, left of the cell in `row`.
+    fn vertical_wall(&self, line: usize, row: usize) -> bool {
+        line == 0 || line == self.settings.columns || self.has_wall((row, line - 1), RIGHT)
+    }
+
+    /// Whether a horizontal wall reaches the joint where `line` crosses the vertical grid line `joint`.
+    fn horizontal_joint(&self, line: usize, joint: usize) -> bool {
+        (joint > 0 && self.horizontal_wall(line, joint - 1)) || (joint < self.settings.columns && self.horizontal_wall(line, joint))
+    }
+
+    /// The distance along the grid lines to the start of a piece, alternating between joints and the walls between them.
+    fn offset(&self, piece: usize) -> i32 {
+        let (cell, thickness) = (self.settings.cell_size as i32, self.settings.wall_thickness as i32);
+        (piece / 2) as i32 * cell + if piece % 2 == 1 { thickness } else { 0 }
+    }
+
+    /// The walls as rectangles of `(x, y, width, height)` micro-bricks.
+    /// <br> Each grid line is split into joints and the walls between them. Runs of pieces along a line merge
+    /// <br> into one rectangle, and joints belong to the horizontal walls reaching them so none overlap.
+    fn wall_rectangles(&self) -> Vec<(i32, i32, i32, i32)> {
+        let RowsSettings { columns, rows, wall_thickness, .. } = self.settings;
+        let thickness = wall_thickness as i32;
+        let mut rectangles = Vec::new();
+
+        let mut runs = |pieces: Vec<bool>, place: &dyn Fn(i32, i32) -> (i32, i32, i32, i32)| {
+            let mut start = None;
+            for (piece, &present) in pieces.iter().chain([&false]).enumerate() {
+                match (start, present) {
+                    (None, true) => start = Some(piece),
+                    (Some(first), false) => {
+                        let (begin, end) = (self.offset(first), self.offset(piece));
+                        rectangles.push(place(begin, end - begin));
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+        };
+
+        for line in 0..=rows {
+            let pieces = (0..=2 * columns)
+                .map(|piece| match piece % 2 {
+                    0 => self.horizontal_joint(line, piece / 2),
+                    _ => self.horizontal_wall(line, piece / 2),
+                })
+                .collect();
+            let y = self.offset(2 * line);
+            runs(pieces, &|x, length| (x, y, length, thickness));
+        }
+
+        for line in 0..=columns {
+            let pieces = (0..=2 * rows)
+                .map(|piece| match piece % 2 {
+                    0 => {
+                        let joint = piece / 2;
+                        let above = joint > 0 && self.vertical_wall(line, joint - 1);
+                        let below = joint < rows && self.vertical_wall(line, joint);
+                        (above || below) && !self.horizontal_joint(joint, line)
+                    }
+                    _ => self.vertical_wall(line, piece / 2),
+                })
+                .collect();
+            let x = self.offset(2 * line);
+            runs(pieces, &|y, length| (x, y, thickness, length));
+        }
+
+        rectangles
+    }
+
+    /// The middle of the walls along a grid line, across the debug images.
+    fn line_centre(&self, line: usize) -> f32 {
+        (PADDING as i32 + self.offset(2 * line)) as f32 + self.settings.wall_thickness as f32 / 2.0
+    }
+}
+
+impl MazeTopology for GridMaze {
+    fn loops(&self) -> usize {
+        self.loops
+    }
+
+    fn canvas_size(&self) -> (u32, u32) {
+        let RowsSettings { columns, rows, cell_size, wall_thickness, .. } = self.settings;
+        let size = |cells: usize| cells as u32 * cell_size + wall_thickness + 2 * PADDING;
+        (size(columns), size(rows))
+    }
+
+    fn cell_centre(&self, (row, column): (usize, usize)) -> (f32, f32) {
+        let centre = |index: usize| self.line_centre(index) + self.settings.cell_size as f32 / 2.0;
+        (centre(column), centre(row))
+    }
+
+    /// Builds the runs of walls along each grid line as single rectangles.
+    fn build_bricks(&self, maze_debug: &mut DebugImage, bricks_debug: &mut DebugImage, bricks: &mut Vec<Brick>) -> f32 {
+
+        let RowsSettings { columns, rows, wall_height, elevation, wall_colour, .. } = self.settings;
+        let rgb = wall_colour.unwrap_or(BLACK);
+        let origin = PADDING as i32;
+
+        for (x, y, w, h) in self.wall_rectangles() {
+            let (x, y) = (x + origin, y + origin);
+            let rectangle = Polygon::from_tuples(&[(x, y), (x + w, y), (x + w, y + h), (x, y + h)]);
+            for rectangle in render_as_squares(rectangle, bricks_debug) {
+                bricks.extend(rectangle_bricks(&rectangle, rgb, wall_height, elevation));
+            }
+        }
+
+        // Every wall from the middle of one joint to the next on the maze image.
+        let line = |line: usize| self.line_centre(line);
+        for row_line in 0..=rows {
+            for column in (0..columns).filter(|&column| self.horizontal_wall(row_line, column)) {
+                maze_debug.draw_line((line(column), line(row_line)), (line(column + 1), line(row_line)), rgb);
+            }
+        }
+        for column_line in 0..=columns {
+            for row in (0..rows).filter(|&row| self.vertical_wall(column_line, row)) {
+                maze_debug.draw_line((line(column_line), line(row)), (line(column_line), line(row + 1)), rgb);
+            }
+        }
+
+        0.0
+    }
+
+    /// The rectangle around the outer walls.
+    fn outline(&self) -> Polygon {
+        let RowsSettings { columns, rows, wall_thickness, .. } = self.settings;
+        let origin = PADDING as i32;
+        let edge = |cells: usize| origin + self.offset(2 * cells) + wall_thickness as i32;
+        let (right, bottom) = (edge(columns), edge(rows));
+        Polygon::from_tuples(&[(origin, origin), (right, origin), (right, bottom), (origin, bottom)])
+    }
+
+    fn slabs(&self) -> Vec<(Slab, i32)> {
+        self.settings.slabs()
+    }
+}
+
+#[cfg(test)]
+mod grid_maze {
+
+    use strum::IntoEnumIterator;
+    use super::*;
+    use crate::maze::generators::Algorithm;
+    use crate::maze::graph::checks::assert_perfect;
+    use crate::maze::Solve;
+
+    #[test]
+    fn run() {
+
+        for algorithm in Algorithm::iter() {
+            let maze = GridMaze::builder().columns(9).rows(6).algorithm(algorithm).seed((5, 4, 3, 2)).generate().unwrap();
+            assert_eq!(maze.cells().len(), 54);
+            assert_perfect(&maze, algorithm);
+            assert_eq!(maze.solve().unwrap().last(), Some(&maze.exit()));
+        }
+
+        // Every micro-brick of wall is covered by exactly one brick.
+        let maze = GridMaze::builder().columns(5).rows(4).cell_size(40).wall_thickness(10).generate().unwrap();
+        let (width, height) = maze.canvas_size();
+        let mut covered = vec![vec![0; width as usize]; height as usize];
+        for (x, y, w, h) in maze.wall_rectangles() {
+            for row in &mut covered[y as usize..(y + h) as usize] {
+                for count in &mut row[x as usize..(x + w) as usize] {
+                    *count += 1;
+                }
+            }
+        }
+        assert!(covered.iter().flatten().all(|&count| count <= 1));
+
+        // The outer walls are closed apart from the entrance and the exit.
+        let (right, bottom) = (5 * 40 + 10, 4 * 40 + 10);
+        let opening = |x: usize, column: usize| (column * 40 + 10..(column + 1) * 40).contains(&x);
+        assert!((0..right).all(|x| covered[0][x] == !opening(x, 0) as i32));
+        assert!((0..right).all(|x| covered[bottom - 1][x] == !opening(x, 4) as i32));
+        assert!((0..bottom).all(|y| covered[y][0] == 1 && covered[y][right - 1] == 1));
+
+        let build = maze.build(false, false);
+        assert_eq!(build.bricks.len(), maze.wall_rectangles().len());
+    }
+}
@@ -0,0 +1,184 @@
+use brickadia::save::Brick;
+
+use crate::draw::colors::BLACK;
+use crate::draw::draw::DebugImage;
+use crate::geometry::polygon::Polygon;
+use super::graph::MazeGraph;
+use super::rows::{Layout, RowsMaze, RowsSettings};
+use super::slab::Slab;
+use super::topology::MazeTopology;
+use super::walls::{rim_outline, Vertex, WallStyle};
+
+/// Space around the walls on the debug images, in micro-bricks.
+const PADDING: u32 = 50;
+
+// The walls of a cell count clockwise from the right, so the wall opposite direction `d` is `d + 3`.
+const RIGHT: usize = 0;
+const LEFT: usize = 3;
+
+/// The corners of a cell around its centre, clockwise from the top right, in half cell widths across
+/// <br> and quarter cell heights down. The wall in direction `d` runs from corner `d` to corner `d + 1`.
+const CORNERS: [(i32, i32); 6] = [(1, -1), (1, 1), (0, 2), (-1, 1), (-1, -1), (0, -2)];
+
+/// Hexagonal cells with pointed tops, each sharing a wall with up to six neighbours. Odd rows are shifted half a cell right.
+#[derive(Clone, Copy, Debug)]
+pub struct HexLayout;
+
+impl Layout for HexLayout {
+    type Walls = [bool; 6];
+
+    const NAME: &'static str = "hex";
+    const WALLS: [bool; 6] = [true; 6];
+    /// The distance between the centres of neighbouring cells, and between the walls on either side of a cell.
+    const CELL_SIZE: u32 = 100;
+    const WALL_THICKNESS: u32 = 30;
+
+    fn step((row, _): (usize, usize), direction: usize) -> (isize, isize) {
+        // The cells above and below an odd row are half a cell further left than those of an even row.
+        let shift = (row % 2) as isize;
+        match direction {
+            0 => (0, 1),
+            1 => (1, shift),
+            2 => (1, shift - 1),
+            3 => (0, -1),
+            4 => (-1, shift - 1),
+            _ => (-1, shift),
+        }
+    }
+
+    fn opposite(direction: usize) -> usize {
+        (direction + 3) % 6
+    }
+}
+
+/// A sigma maze of hexagonal cells with pointed tops, each sharing a wall with up to six neighbours.
+/// <br> Odd rows are shifted half a cell right. The maze is entered through the left wall of the top left cell
+/// <br> and left through the right wall of the bottom right cell. Cells are `(row, column)` pairs.
+pub type HexMaze = RowsMaze<HexLayout>;
+
+impl HexMaze {
+    /// The centre of a cell on the lattice of `CORNERS`.
+    fn centre(&self, (row, column): (usize, usize)) -> Vertex {
+        (2 * column as i32 + 1 + (row % 2) as i32, 3 * row as i32 + 2)
+    }
+
+    /// The corners at either end of the wall in `direction`.
+    fn corners(&self, cell: (usize, usize), direction: usize) -> (Vertex, Vertex) {
+        let (x, y) = self.centre(cell);
+        let corner = |k: usize| (x + CORNERS[k % 6].0, y + CORNERS[k % 6].1);
+        (corner(direction), corner(direction + 1))
+    }
+
+    /// Whether the outer wall of the cell is open in `direction`.
+    fn is_opening(&self, cell: (usize, usize), direction: usize) -> bool {
+        (cell == self.entrance() && direction == LEFT) || (cell == self.exit() && direction == RIGHT)
+    }
+
+    /// Every wall standing, once each, as the corners at either end.
+    fn walls(&self) -> Vec<(Vertex, Vertex)> {
+        let mut walls = Vec::new();
+        for cell in self.cells() {
+            for direction in 0..6 {
+                let standing = match self.neighbour(cell, direction) {
+                    Some(neighbour) => neighbour > cell && self.has_wall(cell, direction),
+                    None => !self.is_opening(cell, direction),
+                };
+                if standing {
+                    walls.push(self.corners(cell, direction));
+                }
+            }
+        }
+        walls
+    }
+
+    /// The position of a lattice point in micro-bricks on the debug images.
+    fn position(&self, (x, y): Vertex) -> (f32, f32) {
+        let RowsSettings { cell_size, wall_thickness, .. } = self.settings;
+        let origin = PADDING as f32 + wall_thickness as f32 / 2.0;
+        let half_width = cell_size as f32 / 2.0;
+        let quarter_height = cell_size as f32 / (2.0 * 3f32.sqrt());
+        (origin + x as f32 * half_width, origin + y as f32 * quarter_height)
+    }
+}
+
+impl MazeTopology for HexMaze {
+    fn loops(&self) -> usize {
+        self.loops
+    }
+
+    fn canvas_size(&self) -> (u32, u32) {
+        let RowsSettings { columns, rows, wall_thickness, .. } = self.settings;
+        let shifted = (rows > 1) as i32;
+        let (x, y) = self.position((2 * columns as i32 + shifted, 3 * rows as i32 + 1));
+        let margin = PADDING as f32 + wall_thickness as f32 / 2.0;
+        ((x + margin).ceil() as u32, (y + margin).ceil() as u32)
+    }
+
+    fn cell_centre(&self, cell: (usize, usize)) -> (f32, f32) {
+        self.position(self.centre(cell))
+    }
+
+    /// The walls between the left and right of cells are straight micro-bricks,
+    /// <br> the walls at 60 degrees to them are stepped with wedges.
+    fn build_bricks(&self, maze_debug: &mut DebugImage, bricks_debug: &mut DebugImage, bricks: &mut Vec<Brick>) -> f32 {
+        let RowsSettings { wall_thickness, wall_height, elevation, wall_colour, .. } = self.settings;
+        let style = WallStyle {
+            rgb: wall_colour.unwrap_or(BLACK),
+            thickness: wall_thickness,
+            height: wall_height,
+            elevation,
+        };
+        style.build(&self.walls(), |vertex| self.position(vertex), maze_debug, bricks_debug, bricks);
+        0.0
+    }
+
+    fn outline(&self) -> Polygon {
+        let rim: Vec<(Vertex, Vertex)> = self.rim().into_iter().map(|(cell, direction)| self.corners(cell, direction)).collect();
+        rim_outline(&rim, |vertex| self.position(vertex), self.settings.wall_thickness)
+    }
+
+    fn slabs(&self) -> Vec<(Slab, i32)> {
+        self.settings.slabs()
+    }
+}
+
+#[cfg(test)]
+mod hex_maze {
+
+    use strum::IntoEnumIterator;
+    use super::*;
+    use crate::maze::generators::Algorithm;
+    use crate::maze::graph::checks::{assert_perfect, assert_walls_drawn_once};
+    use crate::maze::Solve;
+    use crate::metadata::assets::BrickAssets;
+
+    #[test]
+    fn run() {
+
+        for algorithm in Algorithm::iter() {
+            let maze = HexMaze::builder().columns(7).rows(6).algorithm(algorithm).seed((5, 4, 3, 2)).generate().unwrap();
+            assert_eq!(maze.cells().len(), 42);
+            assert_perfect(&maze, algorithm);
+            assert_eq!(maze.solve().unwrap().last(), Some(&maze.exit()));
+        }
+
+        let maze = HexMaze::builder().columns(5).rows(5).generate().unwrap();
+
+        // Inner cells have six neighbours, and every neighbour shares the wall back.
+        assert_eq!(maze.neighbours((2, 2)).len(), 6);
+        assert_eq!(maze.neighbours((1, 2)).len(), 6);
+        for cell in maze.cells() {
+            for neighbour in maze.neighbours(cell) {
+                assert!(maze.neighbours(neighbour).contains(&cell));
+                assert_eq!(maze.has_wall_between(cell, neighbour), maze.has_wall_between(neighbour, cell));
+            }
+        }
+
+        assert_walls_drawn_once(&maze, 6, maze.walls().len());
+
+        // The walls at 60 degrees are stepped with wedges.
+        let build = maze.build(false, false);
+        let wedges = build.bricks.iter().filter(|b| b.asset_name_index == BrickAssets::MicroWedge.index() as u32).count();
+        assert!(wedges > 0 && wedges < build.bricks.len());
+    }
+}
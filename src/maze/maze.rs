@@ -1,30 +1,82 @@
 use brickadia::save::Brick;
-use image::{Rgb, RgbImage};
+use image::{ImageResult, Rgb, RgbImage};
 use rayon::iter::{IntoParallelIterator, ParallelIterator};
 
 use std::f32::{self, consts::PI};
-use std::sync::{Arc, Mutex};
-use crate::draw::colors::{BLACK, RED};
+use crate::draw::colors::BLACK;
 use crate::draw::draw::DebugImage;
-use crate::geometry::arc::{Arc as AngleArc, WedgeArc};
-use crate::geometry::diagonal::diagonal_as_triangles;
+use crate::geometry::arc::{Arc as AngleArc, Precision, WedgeArc, WedgeStyle};
+use crate::geometry::decompose::Partition;
+use crate::geometry::disc::Disc;
+use crate::geometry::line::ThickLine;
+use crate::geometry::polygon::Polygon;
 use crate::utils::indicatif::pb;
 use crate::utils::points::Point;
 use crate::utils::sfc32::{random_range, sfc32};
+use super::braid::braid;
+use super::graph::MazeGraph;
+use super::builder::ThetaMazeBuilder;
+use super::generators::Algorithm;
+use super::generators::growing_tree::Selection;
 use super::cell::Cell;
+use super::slab::{floor_and_ceiling, Slab};
+use super::subdivision::Subdivision;
+use super::topology::MazeTopology;
+
+/// The bricks of a built maze and, when drawn, its debug images.
+pub struct MazeBuild {
+    pub bricks: Vec<Brick>,
+    /// The maze walls and the optional solution path.
+    pub maze_image: Option<RgbImage>,
+    /// The outlines of the bricks the walls were decomposed into.
+    pub bricks_image: Option<RgbImage>,
+    /// The largest gap, in micro-bricks, between the curved walls and their circles.
+    pub deviation: f32,
+}
 
-struct ThetaMaze {
+impl MazeBuild {
+    /// Saves the debug images, if they were drawn.
+    pub fn save_images(&self, maze_path: &str, bricks_path: &str) -> ImageResult<()> {
+        for (image, path) in [(&self.maze_image, maze_path), (&self.bricks_image, bricks_path)] {
+            if let Some(image) = image {
+                image.save(path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct ThetaMaze {
     ring_width: f32,
     rings: usize,
     initial_divisions: usize,
+    /// The divisions of every ring, each a whole multiple of the ring inside it.
+    divisions: Vec<usize>,
     maze: Vec<Vec<Cell>>,
-    backtrack_path: Vec<(usize, usize)>,
     canvas_size: u32,
     centre: Point,
+    /// Thickness of the walls in micro-bricks.
+    pub(super) radius_gap: u32,
+    pub(super) wall_height: u32,
+    /// Height of the bottom of the walls above the ground.
+    pub(super) elevation: i32,
+    /// Colours every wall when set, otherwise the rings are shaded by their distance from the centre.
+    pub(super) wall_colour: Option<Rgb<u8>>,
+    pub(super) wedge_style: WedgeStyle,
+    pub(super) precision: Precision,
+    pub(super) partition: Partition,
+    pub(super) floor: Option<Slab>,
+    pub(super) ceiling: Option<Slab>,
+    pub(super) subdivision: Subdivision,
+    pub(super) algorithm: Algorithm,
+    pub(super) selection: Selection,
+    /// The fraction of dead ends removed after carving.
+    pub(super) braid: f32,
+    loops: usize,
 }
 
 impl ThetaMaze {
-    fn new(ring_width: u32, rings: usize, initial_divisions: usize) -> Self {
+    pub fn new(ring_width: u32, rings: usize, initial_divisions: usize) -> Self {
 
         let dimensions = || -> u32 {
             let diameter = ring_width * (rings as u32 * 2);
@@ -41,173 +93,92 @@ impl ThetaMaze {
             ring_width: ring_width as f32,
             rings,
             initial_divisions,
+            divisions: Subdivision::default().divisions(initial_divisions, rings),
             maze,
             canvas_size: size,
             centre: Point::new(mid, mid),
-            backtrack_path: Vec::new(),
+            radius_gap: 50,
+            wall_height: 100,
+            elevation: 0,
+            wall_colour: None,
+            wedge_style: WedgeStyle::default(),
+            precision: Precision::default(),
+            partition: Partition::default(),
+            floor: None,
+            ceiling: None,
+            subdivision: Subdivision::default(),
+            algorithm: Algorithm::default(),
+            selection: Selection::default(),
+            braid: 0.0,
+            loops: 0,
         }
     }
 
-    fn divisions_in_ring(&self, ring: usize) -> usize {
-        self.initial_divisions * (2 as usize).pow((ring / 2) as u32)
+    pub fn builder() -> ThetaMazeBuilder {
+        ThetaMazeBuilder::default()
     }
 
-    fn unvisited_neighbours(&self, ring: usize, division: usize) -> Vec<(usize, usize)> {
-
-        let mut unvisited: Vec<(usize, usize)> = Vec::new();
-
-        let total_divisions: usize = self.divisions_in_ring(ring);
-
-        let odd_ring: bool = ring % 2 == 1;
-        let even_ring: bool = !odd_ring;
+    pub fn rings(&self) -> usize {
+        self.rings
+    }
 
-        // Iterate over the neighbours of the current division within the ring.
-        let left_division: usize = (division + total_divisions - 1) % total_divisions;
-        let right_division: usize = (division + 1) % total_divisions;
+    pub fn ring_width(&self) -> f32 {
+        self.ring_width
+    }
 
-        for &neighbor in [left_division, right_division].iter() {
-            if !self.maze[ring][neighbor].visited {
-                unvisited.push((ring, neighbor));
-            }
-        }
-        // The inner neighbors are added only if our ring isn't the innermost one:
-        if ring > 0 {
-            // For an odd-numbered ring, the inner ring's divisions align one-to-one with the outer ring.
-            // For an even-numbered ring, each division in the inner ring corresponds to two divisions in the outer ring. 
-            // - To find the matching inner division, you perform integer division with 2.
-            let inner: usize = 
-                if odd_ring { division } else 
-                            { division / 2 }; 
-
-            if !self.maze[ring - 1][inner].visited {
-                unvisited.push((ring - 1, inner));
-            }
-        }
-        // The outer neighbors are added if the ring isn't the outermost one:
-        if ring < self.rings - 1 
-        {
-            if odd_ring {
-                // outer1 is our index * 2
-                let outer1: usize = division * 2;
-                if !self.maze[ring + 1][outer1].visited {
-                    unvisited.push((ring + 1, outer1));
-                }
-                // outer2 is our index * 2 + 1
-                let outer2: usize = outer1 + 1;
-                if !self.maze[ring + 1][outer2].visited {
-                    unvisited.push((ring + 1, outer2));
-                }
-                } 
-            else if even_ring && !self.maze[ring + 1][division].visited {
-                unvisited.push((ring + 1, division));
-            }
-        }
-        unvisited
+    pub fn divisions_in_ring(&self, ring: usize) -> usize {
+        self.divisions[ring]
     }
 
-    fn open_wall_between(&mut self, ringdiv_a: (usize, usize), ringdiv_b: (usize, usize)) {
-        if ringdiv_a.0 == ringdiv_b.0 {
-            // Same ring, right wall
-            let ring = ringdiv_a.0;
-            let div1 = ringdiv_a.1;
-            let div2 = ringdiv_b.1;
-            let next = (div1 + 1) % self.divisions_in_ring(ring);
-            if div2 == next {
-                self.maze[ring][div1].right_wall = false;
-            } else {
-                self.maze[ring][div2].right_wall = false;
-            }
-        } else {
-            // Different rings, open inner wall of outer cell
-            let (_, outer) = if ringdiv_a.0 < ringdiv_b.0 { (ringdiv_a, ringdiv_b) } else { (ringdiv_b, ringdiv_a) };
-            self.maze[outer.0][outer.1].inner_wall = false;
-        }
+    /// The cells of the next ring out for each cell of `ring`.
+    fn outer_ratio(&self, ring: usize) -> usize {
+        self.divisions[ring + 1] / self.divisions[ring]
     }
 
-    fn generate(&mut self, seed: (u32, u32, u32, u32)) {
+    /// Carves the passages of the maze with the selected `Algorithm`.
+    pub fn generate(&mut self, seed: (u32, u32, u32, u32)) {
 
         let (a, b, c, d) = seed;
         let mut rng = sfc32(a, b, c, d);
 
+        self.divisions = self.subdivision.divisions(self.initial_divisions, self.rings);
+        self.maze.clear();
         for i in 0..self.rings {
             let divisions = self.divisions_in_ring(i);
             self.maze.push(vec![Cell::new(); divisions]);
         }
 
-        let mut ring = self.rings - 1;
-        let mut division = 0;
-
-        self.maze[ring][division].visited = true;
-        self.maze[ring][division].outer_wall = false;
+        let entrance = self.entrance();
+        self.visit(entrance);
+        self.maze[entrance.0][entrance.1].outer_wall = false;
 
         for i in 0..self.initial_divisions {
             self.maze[0][i].right_wall = false;
             self.maze[0][i].visited = true;
         }
 
-        let mut backtrack_path = vec![];
-        let mut solution_path = vec![];
-        let mut centre_entry = 0;
-
-        loop {
-            let candidates = self.unvisited_neighbours(ring, division);
-            let candidates_len = candidates.len();
-            if !candidates.is_empty() {
-                backtrack_path.push((ring, division));
-                let next = candidates[random_range(&mut rng, 0.0, candidates_len as f32)];
-                self.open_wall_between((ring, division), next);
-                ring = next.0;
-                division = next.1;
-                self.maze[ring][division].visited = true;
-
-                if ring == 1 {
-                    centre_entry = division;
-                    solution_path = backtrack_path.clone();
-                    solution_path.push((ring, division));
-                    solution_path.push((0, division));
-                }
-            } else if let Some((r, d)) = backtrack_path.pop() {
-                ring = r;
-                division = d;
-            } else {
-                break;
-            }
+        self.algorithm.generator(self.selection).carve(self, entrance, &mut rng);
+
+        // Create an entry to the centre, unless the generator already chose one.
+        let ring_1 = self.divisions_in_ring(1);
+        if (0..ring_1).all(|division| self.maze[1][division].inner_wall) {
+            let centre_entry = random_range(&mut rng, 0.0, ring_1 as f32);
+            self.maze[1][centre_entry].inner_wall = false;
         }
-        // Create an entry to the centre on the backtrack path.
-        self.maze[1][centre_entry].inner_wall = false;
-        self.backtrack_path = solution_path;
+
+        let fraction = self.braid;
+        self.loops = braid(self, fraction, &mut rng);
     }
-    
+
     fn calculate_rgb(&self, ring: usize, total_rings: usize) -> Rgb<u8> {
         let normal = ring as f32 / total_rings as f32;
         let brightness = ((normal - 0.7) * 255.0) as u8;
         Rgb([brightness, brightness, brightness])
     }   
 
-    fn canvas (&self) -> RgbImage {
-        let grey = Rgb([255, 255, 255]);
-        RgbImage::from_pixel(self.canvas_size, self.canvas_size, grey)
-    }
-
-    pub fn build(&self, draw: bool, solve: bool, mut bricks: &mut Vec<Brick>) {
-    
-        let mut maze_debug = if draw {DebugImage::new(Some(self.canvas()), BLACK)} 
-                        else {DebugImage::new(None, BLACK)};
-        
-        let mut bricks_debug = maze_debug.clone();
-
-        self.draw_maze(&mut maze_debug, &mut bricks_debug, &mut bricks);
-        
-        if solve {
-            self.draw_solution_path(&mut maze_debug);
-        }
-        
-        maze_debug.save("maze.png");
-        bricks_debug.save("maze_bricks.png");
-    }
-
+    /// Builds the arc and returns its deviation from the circle.
     fn arc_as_bricks(&self, rgb: Rgb<u8>, mut bricks_debug: &mut DebugImage, mut bricks: &mut Vec<Brick>,
-                    radius: f32, begin_angle: f32, end_angle: f32) {
+                    radius: f32, begin_angle: f32, end_angle: f32) -> f32 {
 
         if radius > 0.0 {
             let radius = radius.round() as u32;
@@ -221,132 +192,294 @@ impl ThetaMaze {
                     centre: self.centre,
                     is_inner_arc: false,
                     radius,
+                    precision: self.precision,
                 }, 
-                radius_gap: 50
+                radius_gap: self.radius_gap,
+                height: self.wall_height,
+                elevation: self.elevation,
+                style: self.wedge_style,
+                partition: self.partition,
             };
             //println!("Arc: {:?}", wedge_arc.arc);
             wedge_arc.compute(&mut bricks_debug, &mut bricks);
+            wedge_arc.deviation()
+        } else {
+            0.0
         }
     }
     
+    /// Builds the wall along `angle` from `radius_inner` to `radius_outer`, `radius_gap` thick.
+    fn radial_wall_as_bricks(&self, rgb: Rgb<u8>, bricks_debug: &mut DebugImage, bricks: &mut Vec<Brick>,
+                    angle: f32, radius_inner: f32, radius_outer: f32) {
+
+        let (cx, cy): (f32, f32) = self.centre.tuple();
+        let point = |radius: f32| Point::from_f32((cx + radius * angle.cos(), cy + radius * angle.sin()));
+
+        ThickLine {
+            rgb,
+            from: point(radius_inner),
+            to: point(radius_outer),
+            width: self.radius_gap,
+            height: self.wall_height,
+            elevation: self.elevation,
+        }.compute(bricks_debug, bricks);
+    }
+
+    /// Builds the rings in parallel, each into its own bricks and debug drawings.
+    /// <br> They're joined in ring order, so the output matches building the rings one by one.
     fn draw_maze(
         &self,
         maze_debug: &mut DebugImage,
         bricks_debug: &mut DebugImage,
         bricks: &mut Vec<Brick>,
-    ) {
-        let maze_debug = Arc::new(Mutex::new(maze_debug));
-        let bricks_debug = Arc::new(Mutex::new(bricks_debug));
-        let bricks = Arc::new(Mutex::new(bricks));
-        let progress = Arc::new(Mutex::new(pb(self.rings as u64, "Building Maze...", "yellow/orange")));
-    
-        // If the maze has holes, it's because 2 contiguous arcs are trying to occupy the same space.
-        (0..self.rings)
+    ) -> f32 {
+        let progress = pb(self.rings as u64, "Building Maze...", "yellow/orange");
+
+        let rings: Vec<(DebugImage, DebugImage, Vec<Brick>, f32)> = (0..self.rings)
             .into_par_iter()
-            .for_each(|ring| {
-                let mut maze_dbg = maze_debug.lock().unwrap();
-                let mut bricks_dbg = bricks_debug.lock().unwrap();
-                let mut bricks_guard = bricks.lock().unwrap();
-                let progress_guard = progress.lock().unwrap();
-    
-                let divisions = self.divisions_in_ring(ring);
-                let arc_angle = 2.0 * std::f32::consts::PI / divisions as f32;
-                let radius_inner = self.ring_width * ring as f32;
-                let radius_outer = radius_inner + self.ring_width;
-                let rgb = self.calculate_rgb(ring, self.rings);
-                let (cx, cy) = self.centre.tuple();
-    
-                let mut current_arcs = [(None, radius_inner, rgb), (None, radius_outer, BLACK)];
-    
-                for division in 0..divisions {
-                    let cell = &self.maze[ring][division];
-                    let start_angle = arc_angle * division as f32;
-                    let end_angle = start_angle + arc_angle;
-    
-                    // Handle inner and outer arcs
-                    for (arc_start, radius, color) in &mut current_arcs {
-                        let is_wall = if *radius == radius_inner {
-                            cell.inner_wall
-                        } else {
-                            ring == self.rings - 1 && cell.outer_wall
-                        };
-    
-                        if is_wall {
-                            if arc_start.is_none() {
-                                *arc_start = Some(start_angle);
-                            }
-                        } else if let Some(start) = *arc_start {
-                            maze_dbg.draw_arc(cx, cy, *radius, start, start_angle, *color);
-                            self.arc_as_bricks(*color, &mut bricks_dbg, &mut bricks_guard, *radius, start, start_angle);
-                            *arc_start = None;
-                        }
-                    }
-    
-                    // Perpendicular intersections
-                    if cell.right_wall {
-                        let (sx, sy) = (
-                            cx + radius_inner * end_angle.cos(),
-                            cy + radius_inner * end_angle.sin(),
-                        );
-                        let (ex, ey) = (
-                            cx + radius_outer * end_angle.cos(),
-                            cy + radius_outer * end_angle.sin(),
-                        );
-                        let (from, to) = ((sx, sy), (ex, ey));
-                        maze_dbg.draw_line(from, to, rgb);
-                        bricks_dbg.draw_line(from, to, RED);
-
-                        /* WORK IN PROGRESS
-                        
-                        // Needs to distinguish between diagonal and straightline approximation
-                        // Lines which are close to straightline are stepped rectangles etc.
-                        
-                        for triangle in diagonal_as_triangles(Point::from_f32(from), Point::from_f32(to)) {
-                            bricks_dbg.draw_outline(&triangle, rgb);
-                        };
-
-                        */
+            .map(|ring| {
+                let mut ring_maze_debug = maze_debug.recorder();
+                let mut ring_bricks_debug = bricks_debug.recorder();
+                let mut ring_bricks = Vec::new();
+                let deviation = self.ring_as_bricks(ring, &mut ring_maze_debug, &mut ring_bricks_debug, &mut ring_bricks);
+                progress.inc(1);
+                (ring_maze_debug, ring_bricks_debug, ring_bricks, deviation)
+            })
+            .collect();
+
+        let mut deviation = 0.0_f32;
+        for (ring_maze_debug, ring_bricks_debug, mut ring_bricks, ring_deviation) in rings {
+            maze_debug.replay(ring_maze_debug);
+            bricks_debug.replay(ring_bricks_debug);
+            bricks.append(&mut ring_bricks);
+            deviation = deviation.max(ring_deviation);
+        }
+        deviation
+    }
 
+    /// Builds the inner arcs and radial walls of the ring, and the outer arcs of the last ring.
+    /// <br> Returns the largest deviation of its arcs from their circles.
+    fn ring_as_bricks(
+        &self,
+        ring: usize,
+        maze_dbg: &mut DebugImage,
+        bricks_dbg: &mut DebugImage,
+        bricks: &mut Vec<Brick>,
+    ) -> f32 {
+        // If the maze has holes, it's because 2 contiguous arcs are trying to occupy the same space.
+        let mut deviation = 0.0_f32;
+
+        let divisions = self.divisions_in_ring(ring);
+        let arc_angle = 2.0 * std::f32::consts::PI / divisions as f32;
+        let radius_inner = self.ring_width * ring as f32;
+        let radius_outer = radius_inner + self.ring_width;
+        let rgb = self.wall_colour.unwrap_or_else(|| self.calculate_rgb(ring, self.rings));
+        let (cx, cy) = self.centre.tuple();
+
+        let mut current_arcs = [(None, radius_inner, rgb), (None, radius_outer, self.wall_colour.unwrap_or(BLACK))];
+
+        for division in 0..divisions {
+            let cell = &self.maze[ring][division];
+            let start_angle = arc_angle * division as f32;
+            let end_angle = start_angle + arc_angle;
+
+            // Handle inner and outer arcs
+            for (arc_start, radius, color) in &mut current_arcs {
+                let is_wall = if *radius == radius_inner {
+                    cell.inner_wall
+                } else {
+                    ring == self.rings - 1 && cell.outer_wall
+                };
+
+                if is_wall {
+                    if arc_start.is_none() {
+                        *arc_start = Some(start_angle);
                     }
+                } else if let Some(start) = *arc_start {
+                    maze_dbg.draw_arc(cx, cy, *radius, start, start_angle, *color);
+                    let arc_deviation = self.arc_as_bricks(*color, bricks_dbg, bricks, *radius, start, start_angle);
+                    deviation = deviation.max(arc_deviation);
+                    *arc_start = None;
                 }
-    
-                // Finalize any remaining arcs
-                for (arc_start, radius, color) in &mut current_arcs {
-                    if let Some(start) = *arc_start {
-                        maze_dbg.draw_arc(cx, cy, *radius, start, arc_angle * divisions as f32, *color);
-                        self.arc_as_bricks(*color, &mut bricks_dbg, &mut bricks_guard, *radius, start, arc_angle * divisions as f32);
-                    }
-                }
-    
-                progress_guard.inc(1);
-            });
-    }
-    
-    fn draw_solution_path(&self, maze_debug: &mut DebugImage) {
+            }
+
+            // Perpendicular intersections
+            if cell.right_wall {
+                let (sx, sy) = (
+                    cx + radius_inner * end_angle.cos(),
+                    cy + radius_inner * end_angle.sin(),
+                );
+                let (ex, ey) = (
+                    cx + radius_outer * end_angle.cos(),
+                    cy + radius_outer * end_angle.sin(),
+                );
+                let (from, to) = ((sx, sy), (ex, ey));
+                maze_dbg.draw_line(from, to, rgb);
+                self.radial_wall_as_bricks(rgb, bricks_dbg, bricks, end_angle, radius_inner, radius_outer);
+            }
+        }
 
-        if self.backtrack_path.is_empty() {
-            return;
+        // Finalize any remaining arcs
+        for (arc_start, radius, color) in &mut current_arcs {
+            if let Some(start) = *arc_start {
+                maze_dbg.draw_arc(cx, cy, *radius, start, arc_angle * divisions as f32, *color);
+                let arc_deviation = self.arc_as_bricks(*color, bricks_dbg, bricks, *radius, start, arc_angle * divisions as f32);
+                deviation = deviation.max(arc_deviation);
+            }
         }
 
-        let mut end: Option<(f32, f32)> = None;
+        deviation
+    }
+}
+
+impl MazeTopology for ThetaMaze {
+    fn loops(&self) -> usize {
+        self.loops
+    }
+
+    fn canvas_size(&self) -> (u32, u32) {
+        (self.canvas_size, self.canvas_size)
+    }
+
+    /// The middle of the cell halfway across its ring.
+    fn cell_centre(&self, (ring, division): (usize, usize)) -> (f32, f32) {
         let (cx, cy): (f32, f32) = self.centre.tuple();
-    
-        for &(ring, division) in &self.backtrack_path {
+        let arc_angle = 2.0 * PI / self.divisions_in_ring(ring) as f32;
+        let mid_angle = arc_angle * division as f32 + arc_angle / 2.0;
+        let radius = self.ring_width * (ring as f32 + 0.5);
+        (cx + radius * mid_angle.cos(), cy + radius * mid_angle.sin())
+    }
+
+    /// Builds the rings of walls.
+    fn build_bricks(&self, maze_debug: &mut DebugImage, bricks_debug: &mut DebugImage, bricks: &mut Vec<Brick>) -> f32 {
+        self.draw_maze(maze_debug, bricks_debug, bricks)
+    }
+
+    /// The circle around the outer ring, stepped as closely as the walls.
+    fn outline(&self) -> Polygon {
+        Disc {
+            centre: self.centre,
+            radius: (self.ring_width * self.rings as f32).round() as u32,
+            precision: self.precision,
+        }.polygon()
+    }
+
+    fn slabs(&self) -> Vec<(Slab, i32)> {
+        floor_and_ceiling(self.floor, self.ceiling, self.elevation, self.wall_height)
+    }
+
+    fn partition(&self) -> Partition {
+        self.partition
+    }
+}
+
+impl MazeGraph for ThetaMaze {
+    fn rows(&self) -> usize {
+        self.rings
+    }
+
+    fn row_len(&self, ring: usize) -> usize {
+        self.divisions_in_ring(ring)
+    }
+
+    /// The cells sharing a wall with `(ring, division)`, in the order left, right, inner and outer.
+    fn neighbours(&self, (ring, division): (usize, usize)) -> Vec<(usize, usize)> {
+
+        let mut neighbours: Vec<(usize, usize)> = Vec::new();
+
+        let total_divisions: usize = self.divisions_in_ring(ring);
+
+        // Iterate over the neighbours of the current division within the ring.
+        let left_division: usize = (division + total_divisions - 1) % total_divisions;
+        let right_division: usize = (division + 1) % total_divisions;
+
+        neighbours.push((ring, left_division));
+        neighbours.push((ring, right_division));
+
+        // The inner neighbors are added only if our ring isn't the innermost one:
+        if ring > 0 {
+            // Each division of the inner ring is split into `ratio` divisions of this one.
+            // - To find the matching inner division, you perform integer division with the ratio.
+            let inner: usize = division / self.outer_ratio(ring - 1);
+
+            neighbours.push((ring - 1, inner));
+        }
+        // The outer neighbors are added if the ring isn't the outermost one:
+        if ring < self.rings - 1 
+        {
+            // The outer divisions run from our index * ratio to the next division's first.
+            let ratio: usize = self.outer_ratio(ring);
+            for outer in division * ratio..(division + 1) * ratio {
+                neighbours.push((ring + 1, outer));
+            }
+        }
+        neighbours
+    }
+
+    fn is_visited(&self, (ring, division): (usize, usize)) -> bool {
+        self.maze[ring][division].visited
+    }
+
+    fn visit(&mut self, (ring, division): (usize, usize)) {
+        self.maze[ring][division].visited = true;
+    }
+
+    /// Cells in a ring of two divisions share two walls, and are only walled off while both stand.
+    fn has_wall_between(&self, ringdiv_a: (usize, usize), ringdiv_b: (usize, usize)) -> bool {
+        if ringdiv_a.0 == ringdiv_b.0 {
+            let ring = ringdiv_a.0;
             let divisions = self.divisions_in_ring(ring);
-            let arc_angle = 2.0 * PI / divisions as f32;
-            let mid_angle = arc_angle * division as f32 + arc_angle / 2.0;
-            let radius = self.ring_width * (ring as f32 + 0.5);
-            let p = (
-                cx + radius * mid_angle.cos(),
-                cy + radius * mid_angle.sin(),
-            );
-    
-            if let Some(prev) = end {
-                maze_debug.draw_line(prev, p, RED);
+            let right_wall = |(left, right): (usize, usize)| {
+                (left + 1) % divisions != right || self.maze[ring][left].right_wall
+            };
+            right_wall((ringdiv_a.1, ringdiv_b.1)) && right_wall((ringdiv_b.1, ringdiv_a.1))
+        } else {
+            let outer = if ringdiv_a.0 < ringdiv_b.0 { ringdiv_b } else { ringdiv_a };
+            self.maze[outer.0][outer.1].inner_wall
+        }
+    }
+
+    fn open_wall_between(&mut self, ringdiv_a: (usize, usize), ringdiv_b: (usize, usize)) {
+        if ringdiv_a.0 == ringdiv_b.0 {
+            // Same ring, right wall
+            let ring = ringdiv_a.0;
+            let div1 = ringdiv_a.1;
+            let div2 = ringdiv_b.1;
+            let next = (div1 + 1) % self.divisions_in_ring(ring);
+            if div2 == next {
+                self.maze[ring][div1].right_wall = false;
+            } else {
+                self.maze[ring][div2].right_wall = false;
             }
-            end = Some(p);
+        } else {
+            // Different rings, open inner wall of outer cell
+            let (_, outer) = if ringdiv_a.0 < ringdiv_b.0 { (ringdiv_a, ringdiv_b) } else { (ringdiv_b, ringdiv_a) };
+            self.maze[outer.0][outer.1].inner_wall = false;
         }
     }
+
+    fn entrance(&self) -> (usize, usize) {
+        (self.rings - 1, 0)
+    }
+
+    /// The cells of the centre.
+    fn goals(&self) -> Vec<(usize, usize)> {
+        (0..self.divisions_in_ring(0)).map(|division| (0, division)).collect()
+    }
+
+    /// The centre is carved as a single open room.
+    fn is_carvable(&self, (ring, _): (usize, usize)) -> bool {
+        ring > 0
+    }
+
+    fn borders_goal(&self, (ring, _): (usize, usize)) -> bool {
+        ring == 1
+    }
+
+    /// Opens the inner wall of a cell in ring 1, joining it to the centre.
+    fn open_goal(&mut self, (ring, division): (usize, usize)) {
+        self.maze[ring][division].inner_wall = false;
+    }
 }
 
 
@@ -354,7 +487,6 @@ impl ThetaMaze {
 #[cfg(test)]
 mod generate_maze {
 
-    use std::env;
     use crate::metadata::save::save_bricks;
     use super::*;
     
@@ -373,12 +505,112 @@ mod generate_maze {
         let draw = true; // computes a canvas based on the dimensions and draws a debug image of the maze.
         let solve = false; // solves the maze with a pathfinding algorithm used to construct it.
     
-        let mut bricks: Vec<Brick> = Vec::new();
-        maze.build(draw, solve, &mut bricks);
+        let build = maze.build(draw, solve);
+        build.save_images("maze.png", "maze_bricks.png").unwrap();
 
-        unsafe { env::set_var("NAME", "Maze"); }
+        save_bricks(build.bricks, "Maze", None).unwrap();
+    }
 
-        save_bricks(bricks);
+}
+
+#[cfg(test)]
+mod parallel_build {
+
+    use super::*;
+    use rayon::ThreadPoolBuilder;
+
+    #[test]
+    fn run() {
+        let maze = ThetaMaze::builder().rings(6).ring_width(80).seed((3, 1, 4, 1)).generate().unwrap();
+
+        // The rings are built in parallel, but joined as if built one after another.
+        let serial = ThreadPoolBuilder::new().num_threads(1).build().unwrap().install(|| maze.build(true, false));
+        let parallel = ThreadPoolBuilder::new().num_threads(4).build().unwrap().install(|| maze.build(true, false));
+
+        let describe = |build: &MazeBuild| build.bricks.iter().map(|brick| format!("{:?}", brick)).collect::<Vec<_>>();
+        assert_eq!(describe(&serial), describe(&parallel));
+        assert_eq!(serial.deviation, parallel.deviation);
+        assert!(serial.maze_image == parallel.maze_image);
+        assert!(serial.bricks_image == parallel.bricks_image);
     }
+}
+
+#[cfg(test)]
+mod subdivided_rings {
+
+    use super::*;
+    use crate::maze::Solve;
+
+    #[test]
+    fn run() {
+        let maze = ThetaMaze::builder()
+            .rings(12)
+            .initial_divisions(3)
+            .subdivision(Subdivision::ArcLength(1.2))
+            .seed((2, 7, 1, 8))
+            .generate()
+            .unwrap();
+
+        // Some rings split their cells three or more ways, and every neighbour shares the wall back.
+        assert!((0..11).any(|ring| maze.outer_ratio(ring) > 2));
+        for cell in maze.cells() {
+            for neighbour in maze.neighbours(cell) {
+                assert!(maze.neighbours(neighbour).contains(&cell), "{:?} and {:?} disagree", cell, neighbour);
+            }
+        }
 
+        let cells = maze.cells();
+        let passages: usize = cells.iter().map(|&cell| maze.passages(cell).len()).sum();
+        assert_eq!(passages / 2, cells.len() - 1);
+        assert!(maze.solve().is_some());
+    }
+}
+
+#[cfg(test)]
+mod radial_walls {
+
+    use super::*;
+    use brickadia::save::Size;
+    use crate::metadata::assets::BrickAssets;
+
+    #[test]
+    fn run() {
+        let maze = ThetaMaze::builder().rings(4).ring_width(100).generate().unwrap();
+        let (cx, cy): (f32, f32) = maze.centre.tuple();
+        let angle = PI / 3.0;
+        let mut bricks = Vec::new();
+        maze.radial_wall_as_bricks(BLACK, &mut DebugImage::new(None, BLACK), &mut bricks, angle, 200.0, 300.0);
+
+        // How far along and across the wall each corner of the bricks lies, from the centre of the maze.
+        // A wedge fills three corners of its box, the one it cuts off is the farthest across the wall.
+        let offset = |(x, y): (f32, f32)| {
+            let (dx, dy) = (x - cx, y - cy);
+            (dx * angle.cos() + dy * angle.sin(), (dy * angle.cos() - dx * angle.sin()).abs())
+        };
+        let (along, across): (Vec<f32>, Vec<f32>) = bricks
+            .iter()
+            .flat_map(|brick| {
+                let Size::Procedural(x, y, _) = brick.size else { panic!("unsized brick") };
+                let (px, py) = (brick.position.0 as f32 / 2.0, brick.position.1 as f32 / 2.0);
+                let (hx, hy) = (x as f32 / 2.0, y as f32 / 2.0);
+                let mut corners: Vec<(f32, f32)> =
+                    [(px - hx, py - hy), (px + hx, py - hy), (px - hx, py + hy), (px + hx, py + hy)].map(offset).into();
+                if brick.asset_name_index == BrickAssets::MicroWedge.index() as u32 {
+                    corners.sort_by(|a, b| a.1.total_cmp(&b.1));
+                    corners.pop();
+                }
+                corners
+            })
+            .unzip();
+        let wedges = bricks.iter().filter(|b| b.asset_name_index == BrickAssets::MicroWedge.index() as u32).count();
+        assert!(wedges > 0, "a wall at 60 degrees is stepped with wedges");
+
+        // The wall runs from the inner radius to the outer one, within a few micro-bricks, and is as thick as the walls.
+        let shortest = along.iter().cloned().fold(f32::MAX, f32::min);
+        let longest = along.iter().cloned().fold(f32::MIN, f32::max);
+        let widest = across.iter().cloned().fold(0.0, f32::max);
+        assert!((shortest - 200.0).abs() < 3.0, "the wall starts {} from the centre", shortest);
+        assert!((longest - 300.0).abs() < 3.0, "the wall ends {} from the centre", longest);
+        assert!(widest <= maze.radius_gap as f32 / 2.0 + 3.0, "the wall reaches {} from its middle", widest);
+    }
 }
@@ -1,2 +1,33 @@
+mod braid;
 mod cell;
-mod maze;
\ No newline at end of file
+mod walls;
+pub mod builder;
+pub mod delta;
+pub mod generators;
+pub mod graph;
+pub mod grid;
+pub mod hex;
+pub mod maze;
+pub mod rows;
+pub mod search;
+pub mod slab;
+pub mod solver;
+pub mod stats;
+pub mod subdivision;
+pub mod topology;
+
+pub use builder::{DeltaMazeBuilder, GridMazeBuilder, HexMazeBuilder, RowsMazeBuilder, ThetaMazeBuilder};
+pub use generators::Algorithm;
+pub use generators::growing_tree::Selection;
+pub use delta::{DeltaLayout, DeltaMaze};
+pub use graph::MazeGraph;
+pub use grid::{GridLayout, GridMaze};
+pub use hex::{HexLayout, HexMaze};
+pub use maze::{MazeBuild, ThetaMaze};
+pub use rows::{Layout, RowsMaze};
+pub use search::{search_seeds, Constraint};
+pub use slab::Slab;
+pub use solver::{Distances, Solve};
+pub use stats::{MazeStats, Measure, RowStats};
+pub use subdivision::Subdivision;
+pub use topology::MazeTopology;
\ No newline at end of file
@@ -0,0 +1,214 @@
+use image::Rgb;
+
+use crate::utils::sfc32::sfc32;
+use super::braid::braid;
+use super::builder::RowsMazeBuilder;
+use super::generators::Algorithm;
+use super::generators::growing_tree::Selection;
+use super::graph::MazeGraph;
+use super::slab::{floor_and_ceiling, Slab};
+
+/// How the cells of a grid, hex or delta maze fit together.
+/// <br> Each cell keeps a wall in every direction, in step with the neighbour on the other side.
+pub trait Layout {
+    /// The walls of a cell, one per direction.
+    type Walls: AsRef<[bool]> + AsMut<[bool]> + Clone + Send + Sync;
+
+    /// The name of the layout, in the messages of settings it can't build.
+    const NAME: &'static str;
+
+    /// The walls of a cell before carving, all standing.
+    const WALLS: Self::Walls;
+
+    /// The size of the cells in micro-bricks, unless set.
+    const CELL_SIZE: u32;
+
+    /// The thickness of the walls in micro-bricks, unless set.
+    const WALL_THICKNESS: u32;
+
+    /// The rows and columns from a cell to its neighbour in `direction`.
+    fn step(cell: (usize, usize), direction: usize) -> (isize, isize);
+
+    /// The direction of the same wall, seen from the neighbour on the other side.
+    fn opposite(direction: usize) -> usize;
+
+    /// Whether walls of `wall_thickness` leave room inside cells of `cell_size`.
+    fn leaves_room(cell_size: u32, wall_thickness: u32) -> bool {
+        wall_thickness < cell_size
+    }
+}
+
+/// The size, walls and carving of a grid, hex or delta maze.
+#[derive(Clone, Debug)]
+pub(super) struct RowsSettings {
+    pub(super) columns: usize,
+    pub(super) rows: usize,
+    pub(super) cell_size: u32,
+    /// Thickness of the walls in micro-bricks.
+    pub(super) wall_thickness: u32,
+    pub(super) wall_height: u32,
+    /// Height of the bottom of the walls above the ground.
+    pub(super) elevation: i32,
+    /// Colours every wall, black by default.
+    pub(super) wall_colour: Option<Rgb<u8>>,
+    pub(super) floor: Option<Slab>,
+    pub(super) ceiling: Option<Slab>,
+    pub(super) algorithm: Algorithm,
+    pub(super) selection: Selection,
+    /// The fraction of dead ends removed after carving.
+    pub(super) braid: f32,
+}
+
+impl RowsSettings {
+    pub(super) fn new<L: Layout>(columns: usize, rows: usize, cell_size: u32) -> Self {
+        Self {
+            columns,
+            rows,
+            cell_size,
+            wall_thickness: L::WALL_THICKNESS,
+            wall_height: 100,
+            elevation: 0,
+            wall_colour: None,
+            floor: None,
+            ceiling: None,
+            algorithm: Algorithm::default(),
+            selection: Selection::default(),
+            braid: 0.0,
+        }
+    }
+
+    /// The floor and ceiling, each with the elevation of its bottom.
+    pub(super) fn slabs(&self) -> Vec<(Slab, i32)> {
+        floor_and_ceiling(self.floor, self.ceiling, self.elevation, self.wall_height)
+    }
+}
+
+/// A maze of `columns` cells across and `rows` down, laid out by `L`, entered at the top left cell
+/// <br> and left at the bottom right cell. Cells are `(row, column)` pairs.
+pub struct RowsMaze<L: Layout> {
+    pub(super) settings: RowsSettings,
+    /// The walls of every cell, by row and column.
+    pub(super) cell_walls: Vec<Vec<L::Walls>>,
+    visited: Vec<Vec<bool>>,
+    pub(super) loops: usize,
+}
+
+impl<L: Layout> RowsMaze<L> {
+    pub fn new(columns: usize, rows: usize, cell_size: u32) -> Self {
+        Self::with_settings(RowsSettings::new::<L>(columns, rows, cell_size))
+    }
+
+    pub(super) fn with_settings(settings: RowsSettings) -> Self {
+        Self { settings, cell_walls: Vec::new(), visited: Vec::new(), loops: 0 }
+    }
+
+    pub fn builder() -> RowsMazeBuilder<L> {
+        RowsMazeBuilder::default()
+    }
+
+    pub fn columns(&self) -> usize {
+        self.settings.columns
+    }
+
+    pub fn cell_size(&self) -> u32 {
+        self.settings.cell_size
+    }
+
+    /// The cell with the opening out of the maze.
+    pub fn exit(&self) -> (usize, usize) {
+        (self.settings.rows - 1, self.settings.columns - 1)
+    }
+
+    /// Carves the passages of the maze with the selected `Algorithm`.
+    pub fn generate(&mut self, seed: (u32, u32, u32, u32)) {
+
+        let (a, b, c, d) = seed;
+        let mut rng = sfc32(a, b, c, d);
+
+        let RowsSettings { columns, rows, .. } = self.settings;
+        self.cell_walls = vec![vec![L::WALLS; columns]; rows];
+        self.visited = vec![vec![false; columns]; rows];
+
+        let entrance = self.entrance();
+        self.visit(entrance);
+        self.settings.algorithm.generator(self.settings.selection).carve(self, entrance, &mut rng);
+
+        let fraction = self.settings.braid;
+        self.loops = braid(self, fraction, &mut rng);
+    }
+
+    /// The cell across the wall in `direction`, if it's inside the maze.
+    pub(super) fn neighbour(&self, cell: (usize, usize), direction: usize) -> Option<(usize, usize)> {
+        let (dr, dc) = L::step(cell, direction);
+        let (r, c) = (cell.0 as isize + dr, cell.1 as isize + dc);
+        ((0..self.settings.rows as isize).contains(&r) && (0..self.settings.columns as isize).contains(&c))
+            .then_some((r as usize, c as usize))
+    }
+
+    /// The direction of the wall between two neighbouring cells, seen from `a`.
+    pub(super) fn direction(&self, a: (usize, usize), b: (usize, usize)) -> usize {
+        (0..L::WALLS.as_ref().len())
+            .find(|&direction| self.neighbour(a, direction) == Some(b))
+            .expect("the cells aren't neighbours")
+    }
+
+    /// The walls along the edge of the maze, openings included, as the cell inside and the direction of the wall.
+    pub(super) fn rim(&self) -> Vec<((usize, usize), usize)> {
+        self.cells()
+            .into_iter()
+            .flat_map(|cell| (0..L::WALLS.as_ref().len()).map(move |direction| (cell, direction)))
+            .filter(|&(cell, direction)| self.neighbour(cell, direction).is_none())
+            .collect()
+    }
+
+    /// Whether the wall in `direction` of the cell stands. Only walls between cells are kept.
+    pub(super) fn has_wall(&self, (row, column): (usize, usize), direction: usize) -> bool {
+        self.cell_walls[row][column].as_ref()[direction]
+    }
+}
+
+impl<L: Layout> MazeGraph for RowsMaze<L> {
+    fn rows(&self) -> usize {
+        self.settings.rows
+    }
+
+    fn row_len(&self, _row: usize) -> usize {
+        self.settings.columns
+    }
+
+    /// The cells sharing a wall with `cell`, in the order of the directions of the layout.
+    fn neighbours(&self, cell: (usize, usize)) -> Vec<(usize, usize)> {
+        (0..L::WALLS.as_ref().len()).filter_map(|direction| self.neighbour(cell, direction)).collect()
+    }
+
+    fn is_visited(&self, (row, column): (usize, usize)) -> bool {
+        self.visited[row][column]
+    }
+
+    fn visit(&mut self, (row, column): (usize, usize)) {
+        self.visited[row][column] = true;
+    }
+
+    fn has_wall_between(&self, a: (usize, usize), b: (usize, usize)) -> bool {
+        self.has_wall(a, self.direction(a, b))
+    }
+
+    fn open_wall_between(&mut self, a: (usize, usize), b: (usize, usize)) {
+        let direction = self.direction(a, b);
+        self.cell_walls[a.0][a.1].as_mut()[direction] = false;
+        self.cell_walls[b.0][b.1].as_mut()[L::opposite(direction)] = false;
+    }
+
+    fn entrance(&self) -> (usize, usize) {
+        (0, 0)
+    }
+
+    fn goals(&self) -> Vec<(usize, usize)> {
+        vec![self.exit()]
+    }
+
+    /// The entrance and the exit each open through the outer wall.
+    fn openings(&self, cell: (usize, usize)) -> usize {
+        (cell == self.entrance()) as usize + (cell == self.exit()) as usize
+    }
+}
@@ -0,0 +1,169 @@
+use std::fmt;
+use std::str::FromStr;
+
+use indicatif::ParallelProgressIterator;
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use strum_macros::{Display, EnumString};
+
+use crate::utils::indicatif::pb;
+use super::stats::MazeStats;
+
+/// A `MazeStats` value a constraint can compare against.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Display, EnumString)]
+#[strum(serialize_all = "snake_case", ascii_case_insensitive)]
+pub enum Metric {
+    DeadEnds,
+    Junctions,
+    LongestCorridor,
+    SolutionLength,
+    SolutionRatio,
+    RiverFactor,
+    AverageBranchDepth,
+}
+
+impl MazeStats {
+    pub fn metric(&self, metric: Metric) -> f32 {
+        match metric {
+            Metric::DeadEnds => self.dead_ends as f32,
+            Metric::Junctions => self.junctions as f32,
+            Metric::LongestCorridor => self.longest_corridor as f32,
+            Metric::SolutionLength => self.solution_length as f32,
+            Metric::SolutionRatio => self.solution_ratio,
+            Metric::RiverFactor => self.river_factor,
+            Metric::AverageBranchDepth => self.average_branch_depth,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    const SYMBOLS: [(&'static str, Comparison); 5] = [
+        ("<=", Self::LessOrEqual),
+        (">=", Self::GreaterOrEqual),
+        ("<", Self::Less),
+        (">", Self::Greater),
+        ("=", Self::Equal),
+    ];
+
+    fn symbol(self) -> &'static str {
+        Self::SYMBOLS.iter().find(|(_, comparison)| *comparison == self).unwrap().0
+    }
+}
+
+/// A bound on one metric, parsed from expressions such as `solution_ratio >= 0.6` or `dead_ends <= 20`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Constraint {
+    pub metric: Metric,
+    pub comparison: Comparison,
+    pub value: f32,
+}
+
+impl Constraint {
+    pub fn accepts(&self, stats: &MazeStats) -> bool {
+        let actual = stats.metric(self.metric);
+        match self.comparison {
+            Comparison::Less => actual < self.value,
+            Comparison::LessOrEqual => actual <= self.value,
+            Comparison::Equal => actual == self.value,
+            Comparison::GreaterOrEqual => actual >= self.value,
+            Comparison::Greater => actual > self.value,
+        }
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (symbol, comparison) = Comparison::SYMBOLS
+            .into_iter()
+            .find(|(symbol, _)| s.contains(symbol))
+            .ok_or_else(|| format!("'{}' has no comparison, expected one of <, <=, =, >=, >", s))?;
+
+        let (metric, value) = s.split_once(symbol).unwrap();
+        let metric = metric.trim().parse::<Metric>().map_err(|_| format!("unknown metric '{}'", metric.trim()))?;
+        let value = value.trim().parse::<f32>().map_err(|e| format!("'{}': {}", value.trim(), e))?;
+
+        Ok(Self { metric, comparison, value })
+    }
+}
+
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {} {}", self.metric, self.comparison.symbol(), self.value)
+    }
+}
+
+/// The `count` seeds following `first`, varying its last value.
+pub fn seeds(first: (u32, u32, u32, u32), count: u32) -> impl Iterator<Item = (u32, u32, u32, u32)> {
+    let (a, b, c, d) = first;
+    (0..count).map(move |i| (a, b, c, d.wrapping_add(i)))
+}
+
+/// Measures the maze `measure` generates for each of `count` seeds in parallel, and keeps the ones meeting every constraint.
+/// <br> Works with any layout. The results are in seed order, so the same search always finds the same seeds.
+pub fn search_seeds(
+    measure: impl Fn((u32, u32, u32, u32)) -> MazeStats + Sync,
+    first: (u32, u32, u32, u32),
+    count: u32,
+    constraints: &[Constraint],
+) -> Vec<((u32, u32, u32, u32), MazeStats)> {
+    seeds(first, count)
+        .collect::<Vec<_>>()
+        .into_par_iter()
+        .progress_with(pb(count as u64, "Searching Seeds...", "cyan/blue"))
+        .filter_map(|seed| {
+            let stats = measure(seed);
+            constraints
+                .iter()
+                .all(|constraint| constraint.accepts(&stats))
+                .then_some((seed, stats))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod seed_search {
+
+    use super::*;
+    use crate::maze::{DeltaMaze, Measure, ThetaMaze};
+
+    #[test]
+    fn run() {
+
+        let constraint: Constraint = "solution_ratio >= 0.3".parse().unwrap();
+        assert_eq!(constraint.metric, Metric::SolutionRatio);
+        assert_eq!(constraint.comparison, Comparison::GreaterOrEqual);
+        assert_eq!(constraint.to_string().parse::<Constraint>().unwrap(), constraint);
+        assert!("dead_ends 20".parse::<Constraint>().is_err());
+        assert!("dead_endz <= 20".parse::<Constraint>().is_err());
+
+        let builder = ThetaMaze::builder().rings(6).initial_divisions(3);
+        let measure = |seed| builder.clone().seed(seed).generate().unwrap().stats();
+        let constraints = [constraint, "dead_ends <= 12".parse().unwrap()];
+        let found = search_seeds(measure, (1, 2, 3, 0), 64, &constraints);
+
+        assert!(!found.is_empty());
+        for (seed, stats) in &found {
+            assert!(constraints.iter().all(|constraint| constraint.accepts(stats)));
+            assert_eq!(&builder.clone().seed(*seed).generate().unwrap().stats(), stats);
+        }
+        assert_eq!(found.iter().map(|(seed, _)| *seed).collect::<Vec<_>>(),
+            search_seeds(measure, (1, 2, 3, 0), 64, &constraints).iter().map(|(seed, _)| *seed).collect::<Vec<_>>());
+
+        // Any layout can be searched.
+        let delta = |seed| DeltaMaze::builder().columns(9).rows(4).seed(seed).generate().unwrap().stats();
+        for (seed, stats) in search_seeds(delta, (1, 2, 3, 0), 16, &[constraint]) {
+            assert!(stats.solution_ratio >= 0.3);
+            assert_eq!(delta(seed), stats);
+        }
+    }
+}
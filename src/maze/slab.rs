@@ -0,0 +1,50 @@
+use brickadia::save::Brick;
+use image::Rgb;
+use serde::{Deserialize, Serialize};
+use crate::draw::draw::DebugImage;
+use crate::geometry::arc::rectangle_bricks;
+use crate::geometry::decompose::{render_as_squares_with, Partition};
+use crate::geometry::polygon::Polygon;
+use crate::metadata::assets::BrickMaterials;
+
+/// A layer of bricks covering the whole maze, as its floor or ceiling.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Slab {
+    /// `[r, g, b]` of the bricks.
+    pub colour: [u8; 3],
+    pub material: BrickMaterials,
+    /// Vertical size of the bricks.
+    pub thickness: u32,
+}
+
+impl Default for Slab {
+    fn default() -> Self {
+        Self {
+            colour: [120, 120, 120],
+            material: BrickMaterials::Plastic,
+            thickness: 10,
+        }
+    }
+}
+
+impl Slab {
+    /// Covers the `outline` with rectangles of micro-bricks, the bottom of the slab at `elevation`.
+    pub fn compute(&self, outline: &Polygon, partition: Partition, elevation: i32, debug: &mut DebugImage, bricks: &mut Vec<Brick>) {
+        for rectangle in render_as_squares_with(outline.clone(), partition, debug) {
+            bricks.extend(
+                rectangle_bricks(&rectangle, Rgb(self.colour), self.thickness, elevation)
+                    .into_iter()
+                    .map(|brick| Brick { material_index: self.material.index() as u32, ..brick }),
+            );
+        }
+    }
+}
+
+/// The `floor` under walls standing at `elevation` and the `ceiling` resting on top of them,
+/// <br> each with the elevation of its bottom.
+pub fn floor_and_ceiling(floor: Option<Slab>, ceiling: Option<Slab>, elevation: i32, wall_height: u32) -> Vec<(Slab, i32)> {
+    let floor = floor.map(|slab| (slab, elevation - slab.thickness as i32));
+    let ceiling = ceiling.map(|slab| (slab, elevation + wall_height as i32));
+    floor.into_iter().chain(ceiling).collect()
+}
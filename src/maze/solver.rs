@@ -0,0 +1,134 @@
+use std::collections::VecDeque;
+
+use super::graph::MazeGraph;
+
+/// The number of steps from one cell to every other cell, found with a breadth-first search.
+/// <br> Unreachable cells have no distance.
+#[derive(Clone, Debug)]
+pub struct Distances {
+    pub from: (usize, usize),
+    /// Indexed by `[row][index]`, like `[ring][division]` in a theta maze.
+    pub steps: Vec<Vec<Option<usize>>>,
+}
+
+impl Distances {
+    pub fn get(&self, (ring, division): (usize, usize)) -> Option<usize> {
+        self.steps[ring][division]
+    }
+
+    /// The reachable cell furthest from the start, and its distance.
+    pub fn furthest(&self) -> ((usize, usize), usize) {
+        self.cells()
+            .fold((self.from, 0), |furthest, (cell, steps)| if steps > furthest.1 { (cell, steps) } else { furthest })
+    }
+
+    /// Every reachable cell with its distance.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), usize)> + '_ {
+        self.steps.iter().enumerate().flat_map(|(ring, divisions)| {
+            divisions
+                .iter()
+                .enumerate()
+                .filter_map(move |(division, steps)| steps.map(|steps| ((ring, division), steps)))
+        })
+    }
+
+    /// Walks back from `to` through cells one step closer to the start, giving a shortest path.
+    pub fn path_to<M: MazeGraph + ?Sized>(&self, maze: &M, to: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        let mut steps = self.get(to)?;
+        let mut path = vec![to];
+        let mut current = to;
+
+        while steps > 0 {
+            current = maze
+                .passages(current)
+                .into_iter()
+                .find(|&cell| self.get(cell) == Some(steps - 1))
+                .expect("a cell at distance n has a passage to a cell at distance n - 1");
+            path.push(current);
+            steps -= 1;
+        }
+
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Finds paths through the passages of any `MazeGraph`.
+pub trait Solve: MazeGraph {
+    /// The distance from `from` to every cell, walking through open walls.
+    fn distances(&self, from: (usize, usize)) -> Distances {
+        Distances { from, steps: self.breadth_first(&[from]) }
+    }
+
+    /// The distance from the closest of the `sources` to every cell, indexed by `[row][index]`.
+    fn breadth_first(&self, sources: &[(usize, usize)]) -> Vec<Vec<Option<usize>>> {
+        let mut steps: Vec<Vec<Option<usize>>> = (0..self.rows())
+            .map(|row| vec![None; self.row_len(row)])
+            .collect();
+
+        let mut queue = VecDeque::new();
+        for &(ring, division) in sources {
+            steps[ring][division] = Some(0);
+            queue.push_back((ring, division));
+        }
+
+        while let Some((ring, division)) = queue.pop_front() {
+            let next = steps[ring][division].unwrap() + 1;
+            for (r, d) in self.passages((ring, division)) {
+                if steps[r][d].is_none() {
+                    steps[r][d] = Some(next);
+                    queue.push_back((r, d));
+                }
+            }
+        }
+
+        steps
+    }
+
+    /// A shortest path between two cells, including both, if they're connected.
+    fn shortest_path(&self, from: (usize, usize), to: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        self.distances(from).path_to(self, to)
+    }
+
+    /// A shortest path from the entrance to the closest goal, such as the centre of a theta maze.
+    fn solve(&self) -> Option<Vec<(usize, usize)>> {
+        let distances = self.distances(self.entrance());
+        let goal = self
+            .goals()
+            .into_iter()
+            .filter_map(|goal| distances.get(goal).map(|steps| (goal, steps)))
+            .min_by_key(|&(_, steps)| steps)?
+            .0;
+        distances.path_to(self, goal)
+    }
+}
+
+impl<M: MazeGraph + ?Sized> Solve for M {}
+
+#[cfg(test)]
+mod solve_maze {
+
+    use super::*;
+    use crate::maze::ThetaMaze;
+
+    #[test]
+    fn run() {
+
+        let builder = ThetaMaze::builder().rings(8).initial_divisions(4).seed((2, 7, 1, 8));
+
+        for maze in [builder.clone().generate().unwrap(), builder.braid(1.0).generate().unwrap()] {
+            let solution = maze.solve().unwrap();
+
+            assert_eq!(solution.first(), Some(&maze.entrance()));
+            assert_eq!(solution.last().unwrap().0, 0);
+            for pair in solution.windows(2) {
+                assert!(maze.passages(pair[0]).contains(&pair[1]));
+            }
+
+            // Every cell is reachable, and the path is as long as the distance to its end.
+            let distances = maze.distances(maze.entrance());
+            assert_eq!(distances.cells().count(), maze.cells().len() + maze.divisions_in_ring(0));
+            assert_eq!(distances.get(*solution.last().unwrap()), Some(solution.len() - 1));
+        }
+    }
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+
+use super::graph::MazeGraph;
+use super::solver::Solve;
+
+/// Counts for the cells of a single row, such as a ring of a theta maze.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RowStats {
+    pub cells: usize,
+    pub dead_ends: usize,
+    pub junctions: usize,
+    /// Cells on the shortest path from the entrance to the goal.
+    pub solution: usize,
+}
+
+/// Measures of how hard a maze is, computed without building it.
+/// <br> Only the cells the generators carve are counted, leaving out rooms such as the centre of a theta maze.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MazeStats {
+    pub cells: usize,
+    /// Cells with a single way in or out.
+    pub dead_ends: usize,
+    /// Cells with three or more ways in or out.
+    pub junctions: usize,
+    /// The most cells in a row with exactly two ways in or out.
+    pub longest_corridor: usize,
+    /// Cells on the shortest path from the entrance to the goal.
+    pub solution_length: usize,
+    /// The fraction of cells on the solution.
+    pub solution_ratio: f32,
+    /// The fraction of cells with two ways in or out. Long winding passages score close to `1.0`,
+    /// mazes with many short dead ends score low.
+    pub river_factor: f32,
+    /// The average number of steps from a dead end back to the solution.
+    pub average_branch_depth: f32,
+    /// Indexed by row. Ring 0 of a theta maze is the centre and always empty.
+    pub rows: Vec<RowStats>,
+}
+
+/// Measures any `MazeGraph`.
+pub trait Measure: MazeGraph {
+    fn stats(&self) -> MazeStats {
+
+        let cells = self.cells();
+        let solution = self.solve().unwrap_or_default();
+        let solution_cells: Vec<(usize, usize)> = solution.iter().copied().filter(|&cell| self.is_carvable(cell)).collect();
+
+        let mut stats = MazeStats {
+            cells: cells.len(),
+            solution_length: solution_cells.len(),
+            rows: vec![RowStats::default(); self.rows()],
+            ..Default::default()
+        };
+
+        for &cell in &cells {
+            let exits = self.exits(cell);
+            let row_stats = &mut stats.rows[cell.0];
+            row_stats.cells += 1;
+            match exits {
+                1 => row_stats.dead_ends += 1,
+                3.. => row_stats.junctions += 1,
+                _ => {}
+            }
+        }
+        for &(row, _) in &solution_cells {
+            stats.rows[row].solution += 1;
+        }
+
+        stats.dead_ends = stats.rows.iter().map(|row| row.dead_ends).sum();
+        stats.junctions = stats.rows.iter().map(|row| row.junctions).sum();
+        stats.longest_corridor = longest_corridor(self);
+
+        let corridors = cells.iter().filter(|&&cell| self.exits(cell) == 2).count();
+        stats.solution_ratio = stats.solution_length as f32 / stats.cells as f32;
+        stats.river_factor = corridors as f32 / stats.cells as f32;
+
+        // Every step into a branch off the solution is a step the player has to walk back.
+        let depths = self.breadth_first(&solution);
+        let branch_depths: Vec<usize> = cells
+            .iter()
+            .filter(|&&cell| self.is_dead_end(cell))
+            .filter_map(|&(row, index)| depths[row][index])
+            .collect();
+        if !branch_depths.is_empty() {
+            stats.average_branch_depth = branch_depths.iter().sum::<usize>() as f32 / branch_depths.len() as f32;
+        }
+
+        stats
+    }
+}
+
+impl<M: MazeGraph + ?Sized> Measure for M {}
+
+/// The size of the largest group of connected cells with exactly two exits.
+fn longest_corridor<M: MazeGraph + ?Sized>(maze: &M) -> usize {
+    let is_corridor = |cell: (usize, usize)| maze.is_carvable(cell) && maze.exits(cell) == 2;
+    let mut seen: Vec<Vec<bool>> = (0..maze.rows())
+        .map(|row| vec![false; maze.row_len(row)])
+        .collect();
+    let mut longest = 0;
+
+    for start in maze.cells() {
+        if seen[start.0][start.1] || !is_corridor(start) {
+            continue;
+        }
+        seen[start.0][start.1] = true;
+
+        let mut length = 0;
+        let mut stack = vec![start];
+        while let Some(cell) = stack.pop() {
+            length += 1;
+            for (r, i) in maze.passages(cell) {
+                if !seen[r][i] && is_corridor((r, i)) {
+                    seen[r][i] = true;
+                    stack.push((r, i));
+                }
+            }
+        }
+        longest = longest.max(length);
+    }
+
+    longest
+}
+
+impl fmt::Display for MazeStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Cells:                {}", self.cells)?;
+        writeln!(f, "Dead ends:            {}", self.dead_ends)?;
+        writeln!(f, "Junctions:            {}", self.junctions)?;
+        writeln!(f, "Longest corridor:     {}", self.longest_corridor)?;
+        writeln!(f, "Solution length:      {} ({:.0}% of cells)", self.solution_length, self.solution_ratio * 100.0)?;
+        writeln!(f, "River factor:         {:.2}", self.river_factor)?;
+        writeln!(f, "Average branch depth: {:.1}", self.average_branch_depth)?;
+        writeln!(f, " Row  Cells  Dead ends  Junctions  Solution")?;
+        // Rows without carved cells, like the centre of a theta maze, are left out.
+        for (row, stats) in self.rows.iter().enumerate().filter(|(_, stats)| stats.cells > 0) {
+            writeln!(
+                f,
+                "{:>4}  {:>5}  {:>9}  {:>9}  {:>8}",
+                row, stats.cells, stats.dead_ends, stats.junctions, stats.solution
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod maze_stats {
+
+    use super::*;
+    use crate::maze::{Algorithm, HexMaze, ThetaMaze};
+
+    #[test]
+    fn run() {
+
+        let builder = ThetaMaze::builder().rings(8).initial_divisions(4).seed((1, 1, 2, 3));
+
+        let backtracker = builder.clone().generate().unwrap().stats();
+        let prim = builder.clone().algorithm(Algorithm::Prim).generate().unwrap().stats();
+        let braided = builder.braid(1.0).generate().unwrap().stats();
+
+        assert_eq!(backtracker.rows.iter().map(|row| row.cells).sum::<usize>(), backtracker.cells);
+        assert!(backtracker.solution_length > 0 && backtracker.solution_length <= backtracker.cells);
+        assert!(backtracker.longest_corridor > 1);
+
+        // The backtracker winds through long corridors, Prim's branches into short dead ends.
+        assert!(backtracker.river_factor > prim.river_factor);
+        assert!(backtracker.dead_ends < prim.dead_ends);
+
+        assert_eq!(braided.dead_ends, 0);
+        assert_eq!(braided.average_branch_depth, 0.0);
+
+        // Every cell of other layouts is counted.
+        let hex = HexMaze::builder().columns(6).rows(5).generate().unwrap().stats();
+        assert_eq!(hex.cells, 30);
+        assert_eq!(hex.rows.len(), 5);
+        assert!(hex.rows.iter().all(|row| row.cells == 6));
+        assert!(hex.solution_length >= 6);
+    }
+}
@@ -0,0 +1,101 @@
+use std::f32::consts::PI;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// How the rings of a theta maze are divided into more cells as they grow longer.
+/// <br> Each ring has a whole number of cells for every cell of the ring inside it, so their radial walls line up.
+/// <br> Parsed from `doubling` or `arc_length=1.5`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subdivision {
+    /// Doubles the divisions every two rings.
+    #[default]
+    Doubling,
+    /// Splits the cells of a ring when they'd be longer than this many ring widths, halfway across the ring,
+    /// <br> into as few equal parts as bring them back under it.
+    ArcLength(f32),
+}
+
+impl Subdivision {
+    /// The divisions of every ring, from the innermost.
+    /// <br> Saturates rather than overflowing, so configs with too many rings can be checked and rejected.
+    pub fn divisions(&self, initial_divisions: usize, rings: usize) -> Vec<usize> {
+        let mut divisions: Vec<usize> = Vec::with_capacity(rings);
+        for ring in 0..rings {
+            let inner = match divisions.last() {
+                Some(&inner) => inner,
+                None => {
+                    divisions.push(initial_divisions);
+                    continue;
+                }
+            };
+            let split = match *self {
+                Self::Doubling if ring % 2 == 0 => 2,
+                Self::Doubling => 1,
+                Self::ArcLength(max_length) => {
+                    let length = 2.0 * PI * (ring as f32 + 0.5) / inner as f32;
+                    (length / max_length).ceil().max(1.0) as usize
+                }
+            };
+            divisions.push(inner.saturating_mul(split));
+        }
+        divisions
+    }
+}
+
+impl fmt::Display for Subdivision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Doubling => write!(f, "doubling"),
+            Self::ArcLength(max_length) => write!(f, "arc_length={}", max_length),
+        }
+    }
+}
+
+impl FromStr for Subdivision {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let subdivision = match s.split_once('=') {
+            None if s.trim() == "doubling" => Self::Doubling,
+            Some((name, value)) if name.trim() == "arc_length" => {
+                let value = value.trim();
+                Self::ArcLength(value.parse().map_err(|e| format!("'{}': {}", value, e))?)
+            }
+            _ => return Err(format!("unknown subdivision '{}', expected doubling or arc_length=<ring widths>", s)),
+        };
+        match subdivision {
+            Self::ArcLength(max_length) if !max_length.is_finite() || max_length <= 0.0 => {
+                Err("the arc length must be above 0".into())
+            }
+            subdivision => Ok(subdivision),
+        }
+    }
+}
+
+#[cfg(test)]
+mod subdivide_rings {
+
+    use super::*;
+
+    #[test]
+    fn run() {
+        assert_eq!(Subdivision::Doubling.divisions(3, 6), vec![3, 3, 6, 6, 12, 12]);
+
+        // Every ring divides its inner ring evenly, and its cells stay within the arc length halfway across.
+        let divisions = Subdivision::ArcLength(1.5).divisions(4, 100);
+        for (ring, pair) in divisions.windows(2).enumerate() {
+            assert_eq!(pair[1] % pair[0], 0);
+            assert!(2.0 * PI * (ring as f32 + 1.5) / pair[1] as f32 <= 1.5);
+        }
+        assert!(divisions[99] < 1_000);
+
+        for text in ["doubling", "arc_length=1.5"] {
+            assert_eq!(text.parse::<Subdivision>().unwrap().to_string(), text);
+        }
+        assert!("arc_length=0".parse::<Subdivision>().is_err());
+        assert!("halving".parse::<Subdivision>().is_err());
+    }
+}
@@ -0,0 +1,144 @@
+use brickadia::save::Brick;
+use image::RgbImage;
+
+use crate::draw::colors::{BLACK, RED, WHITE};
+use crate::draw::draw::DebugImage;
+use crate::geometry::decompose::Partition;
+use crate::geometry::polygon::Polygon;
+use super::graph::MazeGraph;
+use super::maze::MazeBuild;
+use super::slab::Slab;
+use super::solver::Solve;
+
+/// Where the cells and walls of a maze lie, and how its walls are built into bricks.
+/// <br> Together with `MazeGraph`, everything a layout provides for the generators, solver, stats and export.
+pub trait MazeTopology: MazeGraph + Send + Sync {
+    /// The number of loops the braid pass opened.
+    fn loops(&self) -> usize;
+
+    /// The size of the debug images in pixels, one per micro-brick.
+    fn canvas_size(&self) -> (u32, u32);
+
+    /// The middle of a cell on the debug images, where the solution path passes through it.
+    fn cell_centre(&self, cell: (usize, usize)) -> (f32, f32);
+
+    /// Builds the walls, drawing them on the debug images.
+    /// <br> Returns how far curved walls stray from their true shape, in micro-bricks.
+    fn build_bricks(&self, maze_debug: &mut DebugImage, bricks_debug: &mut DebugImage, bricks: &mut Vec<Brick>) -> f32;
+
+    /// The footprint of the maze on the debug images, out to the outer edge of its walls.
+    fn outline(&self) -> Polygon;
+
+    /// The floor under the walls and the ceiling resting on them, each with the elevation of its bottom.
+    fn slabs(&self) -> Vec<(Slab, i32)>;
+
+    /// How the micro-bricks of the floor and ceiling are merged into rectangles.
+    fn partition(&self) -> Partition {
+        Partition::default()
+    }
+
+    /// Draws the shortest path from the entrance to the goal, from the middle of one cell to the next.
+    fn draw_solution_path(&self, maze_debug: &mut DebugImage) {
+
+        let Some(solution) = self.solve() else {
+            return;
+        };
+
+        for pair in solution.windows(2) {
+            maze_debug.draw_line(self.cell_centre(pair[0]), self.cell_centre(pair[1]), RED);
+        }
+    }
+
+    /// Converts the maze into bricks.
+    /// <br> The debug images are only drawn when `draw` is set.
+    fn build(&self, draw: bool, solve: bool) -> MazeBuild {
+
+        let (width, height) = self.canvas_size();
+        let canvas = draw.then(|| RgbImage::from_pixel(width, height, WHITE));
+        let mut maze_debug = DebugImage::new(canvas, BLACK);
+        let mut bricks_debug = maze_debug.clone();
+        let mut bricks: Vec<Brick> = Vec::new();
+
+        let deviation = self.build_bricks(&mut maze_debug, &mut bricks_debug, &mut bricks);
+
+        // The floor and ceiling would cover the walls, so they're left out of the debug images.
+        let slabs = self.slabs();
+        if !slabs.is_empty() {
+            let outline = self.outline();
+            let mut slabs_debug = DebugImage::new(None, BLACK);
+            for (slab, elevation) in slabs {
+                slab.compute(&outline, self.partition(), elevation, &mut slabs_debug, &mut bricks);
+            }
+        }
+
+        if solve {
+            self.draw_solution_path(&mut maze_debug);
+        }
+
+        MazeBuild {
+            bricks,
+            maze_image: maze_debug.into_image(),
+            bricks_image: bricks_debug.into_image(),
+            deviation,
+        }
+    }
+}
+
+#[cfg(test)]
+mod floors_and_ceilings {
+
+    use std::collections::HashSet;
+    use brickadia::save::Size;
+    use super::*;
+    use crate::geometry::arc::brick_z;
+    use crate::maze::{DeltaMaze, GridMaze, HexMaze, ThetaMaze};
+    use crate::metadata::assets::{BrickAssets, BrickMaterials};
+
+    /// The micro-bricks covered by the boxes of the bricks, seen from above.
+    fn footprint<'a>(bricks: impl Iterator<Item = &'a Brick>) -> HashSet<(i32, i32)> {
+        bricks
+            .filter_map(|brick| match brick.size {
+                Size::Procedural(x, y, _) => Some((brick.position, x as i32, y as i32)),
+                _ => None,
+            })
+            .flat_map(|((x, y, _), width, height)| {
+                let (left, top) = ((x - width) / 2, (y - height) / 2);
+                (left..left + width).flat_map(move |x| (top..top + height).map(move |y| (x, y)))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn run() {
+        let floor = Some(Slab { material: BrickMaterials::Metallic, ..Slab::default() });
+        let ceiling = Some(Slab { thickness: 20, ..Slab::default() });
+        let mazes: [Box<dyn MazeTopology>; 4] = [
+            Box::new(ThetaMaze::builder().rings(4).floor(floor).ceiling(ceiling).generate().unwrap()),
+            Box::new(GridMaze::builder().columns(6).rows(4).floor(floor).ceiling(ceiling).generate().unwrap()),
+            Box::new(HexMaze::builder().columns(6).rows(4).floor(floor).ceiling(ceiling).generate().unwrap()),
+            Box::new(DeltaMaze::builder().columns(7).rows(4).floor(floor).ceiling(ceiling).generate().unwrap()),
+        ];
+
+        for maze in &mazes {
+            let bricks = maze.build(false, false).bricks;
+            let at = |z: i32| bricks.iter().filter(move |brick| brick.position.2 == z);
+
+            // The floor ends where the walls begin and the ceiling begins where they end, over the same outline.
+            let slab = footprint(at(brick_z(10, -10)));
+            assert_eq!(slab, footprint(at(brick_z(20, 100))));
+            assert!(at(brick_z(10, -10)).all(|brick| brick.material_index == BrickMaterials::Metallic.index() as u32));
+
+            // The outline reaches no further than the walls, give or take rounding.
+            let walls = footprint(at(brick_z(100, 0)));
+            let (left, right) = (walls.iter().map(|p| p.0).min().unwrap(), walls.iter().map(|p| p.0).max().unwrap());
+            let (top, bottom) = (walls.iter().map(|p| p.1).min().unwrap(), walls.iter().map(|p| p.1).max().unwrap());
+            assert!(slab.iter().all(|&(x, y)| (left - 1..=right + 1).contains(&x) && (top - 1..=bottom + 1).contains(&y)));
+
+            // Only rounding, and the points the walls run on to beside the openings, reach past it.
+            let is_micro_brick = |brick: &&Brick| brick.asset_name_index == BrickAssets::MicroBrick.index() as u32;
+            let micro_bricks = footprint(at(brick_z(100, 0)).filter(is_micro_brick));
+            let uncovered = micro_bricks.iter().filter(|point| !slab.contains(point)).count();
+            assert!(uncovered * 100 < micro_bricks.len(), "{} of {} micro-bricks of wall past the floor", uncovered, micro_bricks.len());
+        }
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+
+use brickadia::save::Brick;
+use image::Rgb;
+
+use crate::draw::draw::DebugImage;
+use crate::geometry::line::ThickLine;
+use crate::geometry::polygon::Polygon;
+use crate::utils::points::Point;
+
+/// A corner of the cells on a maze's lattice, shared exactly by the walls meeting there.
+pub type Vertex = (i32, i32);
+
+/// The ends of each wall, run on past the corners where they'd leave a notch.
+/// <br> Flat wall ends cover every side of a corner unless the widest angle between the walls meeting there
+/// <br> is over 180 degrees. The two walls either side of that angle then run on until their outer edges meet.
+pub fn mitred_walls(
+    walls: &[(Vertex, Vertex)],
+    position: impl Fn(Vertex) -> (f32, f32),
+    thickness: u32,
+) -> Vec<((f32, f32), (f32, f32))> {
+
+    // The angle each wall leaves its corners at, by the wall and which of its ends is at the corner.
+    let mut corners: HashMap<Vertex, Vec<(f32, usize, usize)>> = HashMap::new();
+    for (wall, &(a, b)) in walls.iter().enumerate() {
+        let (from, to) = (position(a), position(b));
+        let angle = (to.1 - from.1).atan2(to.0 - from.0);
+        corners.entry(a).or_default().push((angle, wall, 0));
+        corners.entry(b).or_default().push((angle + PI, wall, 1));
+    }
+
+    let mut run_on = vec![[0.0_f32; 2]; walls.len()];
+    for leaving in corners.values_mut() {
+        if leaving.len() < 2 {
+            continue;
+        }
+        leaving.sort_by(|a, b| a.0.rem_euclid(TAU).total_cmp(&b.0.rem_euclid(TAU)));
+
+        let gap = |i: usize| {
+            let next = (i + 1) % leaving.len();
+            (leaving[next].0 - leaving[i].0).rem_euclid(TAU)
+        };
+        let widest = (0..leaving.len()).max_by(|&i, &j| gap(i).total_cmp(&gap(j))).unwrap();
+
+        // Skips straight runs, whose gaps are 180 degrees give or take the rounding of the lattice.
+        let inside = TAU - gap(widest);
+        if inside < PI - 1e-3 {
+            let length = thickness as f32 / 2.0 / (inside / 2.0).tan();
+            for (_, wall, end) in [leaving[widest], leaving[(widest + 1) % leaving.len()]] {
+                run_on[wall][end] = length;
+            }
+        }
+    }
+
+    walls
+        .iter()
+        .zip(run_on)
+        .map(|(&(a, b), [before, after])| {
+            let (from, to) = (position(a), position(b));
+            let length = (to.0 - from.0).hypot(to.1 - from.1);
+            let (ux, uy) = ((to.0 - from.0) / length, (to.1 - from.1) / length);
+            ((from.0 - ux * before, from.1 - uy * before), (to.0 + ux * after, to.1 + uy * after))
+        })
+        .collect()
+}
+
+/// The outer edge of the walls around the `rim`, the outward walls of the cells along the edge of a maze.
+/// <br> Follows the rim from corner to corner, moving each corner out to where the outer edges of its walls meet,
+/// <br> as `mitred_walls` runs them on.
+pub fn rim_outline(rim: &[(Vertex, Vertex)], position: impl Fn(Vertex) -> (f32, f32), thickness: u32) -> Polygon {
+    let mut ends: HashMap<Vertex, Vec<Vertex>> = HashMap::new();
+    for &(a, b) in rim {
+        ends.entry(a).or_default().push(b);
+        ends.entry(b).or_default().push(a);
+    }
+
+    // Every corner of the rim joins exactly two of its walls.
+    let mut corners = vec![rim[0].0];
+    let mut next = rim[0].1;
+    while next != corners[0] {
+        let previous = corners[corners.len() - 1];
+        corners.push(next);
+        next = *ends[&next].iter().find(|&&end| end != previous).expect("the rim is a closed loop");
+    }
+
+    let points: Vec<(f32, f32)> = corners.into_iter().map(position).collect();
+    let n = points.len();
+    let area: f32 = (0..n)
+        .map(|i| {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            a.0 * b.1 - b.0 * a.1
+        })
+        .sum();
+
+    // The maze lies left of the walls when the corners turn counterclockwise, right of them otherwise.
+    let outward = |a: (f32, f32), b: (f32, f32)| {
+        let length = (b.0 - a.0).hypot(b.1 - a.1);
+        let (ux, uy) = ((b.0 - a.0) / length, (b.1 - a.1) / length);
+        if area > 0.0 { (uy, -ux) } else { (-uy, ux) }
+    };
+
+    let half = thickness as f32 / 2.0;
+    let outer: Vec<Point> = (0..n)
+        .map(|i| {
+            let (before, at, after) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            let (a, b) = (outward(before, at), outward(at, after));
+            // The point half a thickness out from both walls lies along the sum of their normals.
+            let scale = half / (1.0 + a.0 * b.0 + a.1 * b.1);
+            Point::from_f32((at.0 + (a.0 + b.0) * scale, at.1 + (a.1 + b.1) * scale))
+        })
+        .collect();
+    Polygon::new(&outer)
+}
+
+/// The look of the straight walls between the corners of a lattice.
+pub struct WallStyle {
+    pub rgb: Rgb<u8>,
+    /// Thickness of the walls in micro-bricks.
+    pub thickness: u32,
+    pub height: u32,
+    /// Height of the bottom of the walls above the ground.
+    pub elevation: i32,
+}
+
+impl WallStyle {
+    /// Builds every wall as a `ThickLine` with mitred corners, and draws it on the maze image.
+    pub fn build(
+        &self,
+        walls: &[(Vertex, Vertex)],
+        position: impl Fn(Vertex) -> (f32, f32),
+        maze_debug: &mut DebugImage,
+        bricks_debug: &mut DebugImage,
+        bricks: &mut Vec<Brick>,
+    ) {
+        let mitred = mitred_walls(walls, &position, self.thickness);
+
+        for (&(a, b), (from, to)) in walls.iter().zip(mitred) {
+            maze_debug.draw_line(position(a), position(b), self.rgb);
+
+            ThickLine {
+                rgb: self.rgb,
+                from: Point::from_f32(from),
+                to: Point::from_f32(to),
+                width: self.thickness,
+                height: self.height,
+                elevation: self.elevation,
+            }.compute(bricks_debug, bricks);
+        }
+    }
+}
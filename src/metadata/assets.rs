@@ -1,6 +1,7 @@
 
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
-use strum_macros::{EnumIter, Display};
+use strum_macros::{EnumIter, EnumString, Display};
 
 #[derive(Debug, Clone, Copy, EnumIter, Display, PartialEq)]
 #[repr(usize)]
@@ -25,4 +26,29 @@ impl BrickAssets {
     pub fn names() -> Vec<String> {
         Self::iter().map(|asset| asset.name()).collect()
     }
-}
\ No newline at end of file
+}
+
+/// The materials registered in every save, in index order.
+#[derive(Debug, Clone, Copy, Default, EnumIter, EnumString, Display, PartialEq, Eq, Serialize, Deserialize)]
+#[strum(ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+#[repr(usize)]
+pub enum BrickMaterials {
+    #[default]
+    Plastic,
+    Glass,
+    Glow,
+    Metallic,
+    Hologram,
+}
+impl BrickMaterials {
+    pub fn index(self) -> usize {
+        self as usize
+    }
+    pub fn name(self) -> String {
+        format!("BMC_{}", self)
+    }
+    pub fn names() -> Vec<String> {
+        Self::iter().map(|material| material.name()).collect()
+    }
+}
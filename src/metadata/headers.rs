@@ -1,7 +1,7 @@
 
-use super::assets::BrickAssets;
+use super::assets::{BrickAssets, BrickMaterials};
 use brickadia::save::{BrickOwner, SaveData, User};
-use std::{env, path::PathBuf};
+use std::{env, path::{Path, PathBuf}};
 
 pub fn location() -> PathBuf {
     env::var("LOCALAPPDATA")
@@ -13,7 +13,9 @@ pub fn location() -> PathBuf {
         })
 }
 
-pub fn savedata() -> (SaveData, PathBuf) {
+/// Builds the save headers and the `.brs` path for `name`.
+/// <br> Saves go to the Brickadia builds folder unless a `directory` is given.
+pub fn savedata(name: &str, directory: Option<&Path>) -> (SaveData, PathBuf) {
 
     let mut save = SaveData::default();
 
@@ -22,22 +24,16 @@ pub fn savedata() -> (SaveData, PathBuf) {
         id: "3f5108a0-c929-4e77-a115-21f65096887b".parse().unwrap(),
     };
 
-    let name = if cfg!(test) {
-        std::env::var("NAME").unwrap_or_else(|_| "NAME".into())
-    } else {
-        std::env::args().nth(1).unwrap_or_else(|| "NAME".into())
-    };
-    
     let name = format!("{}.brs", name);
 
-    let path = location().join(name);
+    let path = directory.map(Path::to_path_buf).unwrap_or_else(location).join(name);
 
     save.header1.author = public.clone();
     save.header1.host = Some(public.clone());
     save.header1.description = "This was saved with BrickadiaGen!".into();
     
     save.header2.brick_assets = BrickAssets::names();
-    save.header2.materials.push("BMC_Glass".into());
+    save.header2.materials = BrickMaterials::names();
 
     save.header2.brick_owners.push(BrickOwner::from_user_bricks(public.clone(), 100));
 
@@ -1,15 +1,18 @@
 use std::fs::File;
+use std::io;
+use std::path::Path;
 use brickadia::{save::Brick, write::SaveWriter};
 
-pub fn save_bricks(bricks: Vec<Brick>) {
+/// Writes the bricks to `name.brs`, in the Brickadia builds folder unless a `directory` is given.
+pub fn save_bricks(bricks: Vec<Brick>, name: &str, directory: Option<&Path>) -> io::Result<()> {
 
-    let (mut savedata, path) = super::headers::savedata();
+    let (mut savedata, path) = super::headers::savedata(name, directory);
 
     savedata.bricks = bricks;
 
     println!("Writing save to {} with {} bricks", path.to_string_lossy(), savedata.bricks.len());
 
-    SaveWriter::new(File::create(path).unwrap(), savedata)
+    SaveWriter::new(File::create(path)?, savedata)
         .write()
-        .unwrap();
-}
\ No newline at end of file
+        .map_err(io::Error::other)
+}
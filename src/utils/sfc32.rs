@@ -40,3 +40,14 @@ where
     let rand_value = sfc() as f32 * range;
     (min + rand_value) as usize
 }
+
+/// Shuffles a slice in place with the Fisher-Yates algorithm, using a PRNG closure.
+pub fn shuffle<T, F>(mut sfc: F, items: &mut [T])
+where
+    F: FnMut() -> f64,
+{
+    for i in (1..items.len()).rev() {
+        let j = random_range(&mut sfc, 0.0, (i + 1) as f32);
+        items.swap(i, j);
+    }
+}